@@ -9,9 +9,6 @@ use fbas_analyzer::*;
 use std::collections::HashMap;
 use std::path::Path;
 
-use hex;
-use sha3::{Digest, Sha3_256};
-
 pub fn main() {
     let fbas = Fbas::from_json_file(Path::new("test_data/stellarbeat_nodes_2019-09-17.json"));
 
@@ -22,14 +19,15 @@ pub fn main() {
 
     // Now we can calculate a hash of the FBAS such that, if two FBASs have the same hash, their
     // analysis results will be the same!
-    let fbas_hash = hex::encode(Sha3_256::digest(&fbas.to_json_string().into_bytes()));
+    let fbas_hash = fbas.standard_form_hash();
     println!(
         "SHA3 hash of FBAS in standard form (when converted to JSON): {}",
         fbas_hash
     );
 
     // Now we only need to `do_analysis` when something significant changes in the quorum set
-    // configuration!
+    // configuration! (If you want to keep results across runs, `Analysis::save_cache` and
+    // `Analysis::with_cache` do this for you, using the same hash.)
     let mut results_cache: HashMap<Fbas, CustomResultsStruct> = HashMap::new();
     let analysis_results = if let Some(cached_results) = results_cache.get(&fbas) {
         cached_results.clone()
@@ -4,8 +4,21 @@ use itertools::Itertools;
 
 /// Find all minimal blocking sets in the FBAS.
 pub fn find_minimal_blocking_sets(fbas: &Fbas) -> Vec<NodeIdSet> {
+    find_minimal_blocking_sets_with(fbas, &SearchOptions::new())
+}
+/// Like `find_minimal_blocking_sets`, but searches as configured via `options`. If the search is
+/// cancelled, only the minimal blocking sets found so far are returned.
+pub fn find_minimal_blocking_sets_with(fbas: &Fbas, options: &SearchOptions) -> Vec<NodeIdSet> {
+    find_minimal_blocking_sets_monitored(fbas, &mut options.monitor("minimal blocking sets"))
+}
+pub(crate) fn find_minimal_blocking_sets_monitored(
+    fbas: &Fbas,
+    monitor: &mut SearchMonitor,
+) -> Vec<NodeIdSet> {
     info!("Starting to look for minimal blocking_sets...");
-    let minimal_blocking_sets = find_minimal_sets(fbas, minimal_blocking_sets_finder);
+    let minimal_blocking_sets = find_minimal_sets(fbas, |clusters, fbas| {
+        minimal_blocking_sets_finder(clusters, fbas, monitor)
+    });
     info!(
         "Found {} minimal blocking_sets.",
         minimal_blocking_sets.len()
@@ -13,43 +26,88 @@ pub fn find_minimal_blocking_sets(fbas: &Fbas) -> Vec<NodeIdSet> {
     minimal_blocking_sets
 }
 
-fn minimal_blocking_sets_finder(consensus_clusters: Vec<NodeIdSet>, fbas: &Fbas) -> Vec<NodeIdSet> {
+/// Find all minimal sets of nodes that can block `node`, i.e., that intersect each quorum
+/// containing `node` (see `find_minimal_quorums_containing`). Sets containing `node` itself are
+/// omitted. If `node` isn't part of any quorum, the empty set is returned as the only blocking set.
+pub fn find_minimal_blocking_sets_for(fbas: &Fbas, node: NodeId) -> Vec<NodeIdSet> {
+    find_minimal_blocking_sets_for_monitored(
+        fbas,
+        node,
+        &mut SearchMonitor::new("minimal blocking sets for node", &Cancellation::new(), None),
+    )
+}
+pub(crate) fn find_minimal_blocking_sets_for_monitored(
+    fbas: &Fbas,
+    node: NodeId,
+    monitor: &mut SearchMonitor,
+) -> Vec<NodeIdSet> {
+    let (fbas_anchored, shrink_manager) = fbas.anchored_at(node);
+    if fbas_anchored.number_of_nodes() == 0 {
+        monitor.finish();
+        return vec![bitset![]];
+    }
+    let minimal_blocking_sets = find_minimal_blocking_sets_monitored(&fbas_anchored, monitor);
+    let mut minimal_blocking_sets: Vec<NodeIdSet> = shrink_manager
+        .unshrink_sets(&minimal_blocking_sets)
+        .into_iter()
+        .filter(|blocking_set| !blocking_set.contains(node))
+        .collect();
+    minimal_blocking_sets.sort_unstable();
+    minimal_blocking_sets.sort_by_key(|x| x.len());
+    minimal_blocking_sets
+}
+
+fn minimal_blocking_sets_finder(
+    consensus_clusters: Vec<NodeIdSet>,
+    fbas: &Fbas,
+    monitor: &mut SearchMonitor,
+) -> Vec<NodeIdSet> {
     let mut found_blocking_sets_per_cluster: Vec<Vec<NodeIdSet>> = vec![];
+    let number_of_clusters = consensus_clusters.len();
     for (i, nodes) in consensus_clusters.into_iter().enumerate() {
         debug!("Finding minimal blocking sets in cluster {}...", i);
+        monitor.start_cluster(i, number_of_clusters, nodes.len());
 
         if let Some(symmetric_cluster) = find_symmetric_cluster_in_consensus_cluster(&nodes, fbas) {
             debug!("Cluster contains a symmetric quorum cluster! Extracting blocking sets...");
-            found_blocking_sets_per_cluster.push(symmetric_cluster.to_minimal_blocking_sets(fbas));
+            let blocking_sets = symmetric_cluster.to_minimal_blocking_sets(fbas);
+            monitor.found(blocking_sets.len());
+            found_blocking_sets_per_cluster.push(blocking_sets);
         } else {
             debug!("Sorting nodes by rank...");
             let sorted_nodes = sort_by_rank(nodes.into_iter().collect(), fbas);
             debug!("Sorted.");
 
-            let mut found_blocking_sets: Vec<NodeIdSet> = vec![];
-
-            let unprocessed = sorted_nodes;
-            let mut selection = NodeIdSet::with_capacity(fbas.nodes.len());
-
-            // what remains after we take out `selection`
-            let mut remaining: NodeIdSet = unprocessed.iter().copied().collect();
-
-            // what remains after we take out `selection` + all `unprocessed`
-            let mut max_remaining = NodeIdSet::with_capacity(fbas.nodes.len());
-
             debug!("Collecting blocking_sets...");
-            minimal_blocking_sets_finder_step(
-                &mut unprocessed.into(),
-                &mut selection,
-                &mut remaining,
-                &mut max_remaining,
-                &mut found_blocking_sets,
-                fbas,
-                true,
-            );
+            let found_blocking_sets = search_cluster(monitor, |monitor| {
+                let mut found_blocking_sets: Vec<NodeIdSet> = vec![];
+
+                let unprocessed = sorted_nodes.clone();
+                let mut selection = NodeIdSet::with_capacity(fbas.nodes.len());
+
+                // what remains after we take out `selection`
+                let mut remaining: NodeIdSet = unprocessed.iter().copied().collect();
+
+                // what remains after we take out `selection` + all `unprocessed`
+                let mut max_remaining = NodeIdSet::with_capacity(fbas.nodes.len());
+
+                minimal_blocking_sets_finder_step(
+                    &mut unprocessed.into(),
+                    &mut selection,
+                    &mut remaining,
+                    &mut max_remaining,
+                    &mut found_blocking_sets,
+                    fbas,
+                    monitor,
+                    true,
+                );
+                found_blocking_sets
+            });
             found_blocking_sets_per_cluster.push(found_blocking_sets);
         }
+        monitor.finish_cluster();
     }
+    monitor.finish();
     found_blocking_sets_per_cluster
         .into_iter()
         .map(|blocking_sets_group| blocking_sets_group.into_iter())
@@ -63,41 +121,82 @@ fn minimal_blocking_sets_finder(consensus_clusters: Vec<NodeIdSet>, fbas: &Fbas)
         })
         .collect()
 }
+/// Where `minimal_blocking_sets_finder_step` puts the minimal blocking sets it finds.
+trait FoundBlockingSets {
+    fn add(&mut self, blocking_set: &NodeIdSet);
+    /// Whether adding nodes to a selection of size `selection_size` can still yield blocking sets
+    /// worth adding.
+    fn is_worth_extending(&self, selection_size: usize) -> bool;
+}
+/// Collects all found minimal blocking sets.
+impl FoundBlockingSets for Vec<NodeIdSet> {
+    fn add(&mut self, blocking_set: &NodeIdSet) {
+        self.push(blocking_set.clone());
+        if self.len() % 100_000 == 0 {
+            debug!("...{} blocking_sets found", self.len());
+        }
+    }
+    fn is_worth_extending(&self, _: usize) -> bool {
+        true
+    }
+}
+/// The smallest blocking set found so far, bounding the search by its size.
+impl FoundBlockingSets for NodeIdSet {
+    fn add(&mut self, blocking_set: &NodeIdSet) {
+        // we only ever get here with selections that are smaller than `self`
+        debug!("...found a blocking set of size {}", blocking_set.len());
+        *self = blocking_set.clone();
+    }
+    fn is_worth_extending(&self, selection_size: usize) -> bool {
+        selection_size + 1 < self.len()
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 fn minimal_blocking_sets_finder_step(
     unprocessed: &mut NodeIdDeque,
     selection: &mut NodeIdSet,
     remaining: &mut NodeIdSet,
     max_remaining: &mut NodeIdSet,
-    found_blocking_sets: &mut Vec<NodeIdSet>,
+    found_blocking_sets: &mut impl FoundBlockingSets,
     fbas: &Fbas,
+    monitor: &mut SearchMonitor,
     selection_changed: bool,
 ) {
-    if selection_changed && is_blocked_set(remaining, fbas) {
+    if !monitor.visit() {
+        // return, keeping what we found so far
+    } else if selection_changed && is_blocked_set(remaining, fbas) {
         if is_minimal_for_blocking_set_with_precomputed_blocked_set(selection, remaining, fbas) {
-            found_blocking_sets.push(selection.clone());
-            if found_blocking_sets.len() % 100_000 == 0 {
-                debug!("...{} blocking_sets found", found_blocking_sets.len());
-            }
+            found_blocking_sets.add(selection);
+            monitor.found(1);
         }
+        monitor.complete_branch(unprocessed.len());
+    } else if !found_blocking_sets.is_worth_extending(selection.len()) {
+        monitor.complete_branch(unprocessed.len());
     } else if let Some(current_candidate) = unprocessed.pop_front() {
         selection.insert(current_candidate);
         remaining.remove(current_candidate);
 
-        minimal_blocking_sets_finder_step(
-            unprocessed,
-            selection,
-            remaining,
-            max_remaining,
-            found_blocking_sets,
-            fbas,
-            true,
-        );
+        if monitor.takes_branch(unprocessed.len(), true) {
+            minimal_blocking_sets_finder_step(
+                unprocessed,
+                selection,
+                remaining,
+                max_remaining,
+                found_blocking_sets,
+                fbas,
+                monitor,
+                true,
+            );
+        }
 
         selection.remove(current_candidate);
         remaining.insert(current_candidate);
         max_remaining.insert(current_candidate);
 
-        if is_blocked_set(max_remaining, &fbas) {
+        if !monitor.takes_branch(unprocessed.len(), false) {
+            // explored in another work item
+        } else if is_blocked_set(max_remaining, &fbas) {
             minimal_blocking_sets_finder_step(
                 unprocessed,
                 selection,
@@ -105,14 +204,104 @@ fn minimal_blocking_sets_finder_step(
                 max_remaining,
                 found_blocking_sets,
                 fbas,
+                monitor,
                 false,
             );
+        } else {
+            monitor.complete_branch(unprocessed.len());
         }
         unprocessed.push_front(current_candidate);
         max_remaining.remove(current_candidate);
+    } else {
+        monitor.complete_branch(unprocessed.len());
     }
 }
 
+/// Find one of the smallest minimal blocking sets in the FBAS, via a branch and bound search
+/// that doesn't enumerate all minimal blocking sets. Returns `None` if the FBAS has no quorums.
+pub fn find_smallest_blocking_set(fbas: &Fbas) -> Option<NodeIdSet> {
+    find_smallest_blocking_set_with(fbas, &SearchOptions::new())
+}
+/// Like `find_smallest_blocking_set`, but searches as configured via `options`. If the search is
+/// cancelled, the smallest blocking set found so far (which might not be minimal) is returned.
+pub fn find_smallest_blocking_set_with(fbas: &Fbas, options: &SearchOptions) -> Option<NodeIdSet> {
+    find_smallest_blocking_set_monitored(fbas, &mut options.monitor("smallest blocking set"))
+}
+pub(crate) fn find_smallest_blocking_set_monitored(
+    fbas: &Fbas,
+    monitor: &mut SearchMonitor,
+) -> Option<NodeIdSet> {
+    info!("Starting to look for a smallest blocking set...");
+    let smallest_blocking_set = find_sets(fbas, |clusters, fbas| {
+        smallest_blocking_set_finder(clusters, fbas, monitor)
+    })
+    .pop();
+    if let Some(ref blocking_set) = smallest_blocking_set {
+        info!(
+            "Found a smallest blocking set of size {}.",
+            blocking_set.len()
+        );
+    }
+    smallest_blocking_set
+}
+
+fn smallest_blocking_set_finder(
+    consensus_clusters: Vec<NodeIdSet>,
+    fbas: &Fbas,
+    monitor: &mut SearchMonitor,
+) -> Vec<NodeIdSet> {
+    if consensus_clusters.is_empty() {
+        monitor.finish();
+        return vec![];
+    }
+    // blocking sets must block each cluster, so we combine the smallest blocking sets of all
+    let mut combined_blocking_set = bitset![];
+    let number_of_clusters = consensus_clusters.len();
+    for (i, nodes) in consensus_clusters.into_iter().enumerate() {
+        debug!("Finding a smallest blocking set in cluster {}...", i);
+        monitor.start_cluster(i, number_of_clusters, nodes.len());
+
+        let smallest_blocking_set = if let Some(symmetric_cluster) =
+            find_symmetric_cluster_in_consensus_cluster(&nodes, fbas)
+        {
+            debug!("Cluster contains a symmetric quorum cluster! Extracting blocking sets...");
+            symmetric_cluster
+                .to_minimal_blocking_sets(fbas)
+                .into_iter()
+                .min_by_key(|blocking_set| blocking_set.len())
+                .unwrap_or(nodes)
+        } else {
+            debug!("Sorting nodes by rank...");
+            let sorted_nodes = sort_by_rank(nodes.iter().collect(), fbas);
+            debug!("Sorted.");
+
+            let unprocessed = sorted_nodes;
+            let mut selection = NodeIdSet::with_capacity(fbas.nodes.len());
+            let mut remaining: NodeIdSet = unprocessed.iter().copied().collect();
+            let mut max_remaining = NodeIdSet::with_capacity(fbas.nodes.len());
+
+            // the whole cluster is always blocking and serves as initial bound
+            let mut best = nodes;
+
+            debug!("Searching...");
+            minimal_blocking_sets_finder_step(
+                &mut unprocessed.into(),
+                &mut selection,
+                &mut remaining,
+                &mut max_remaining,
+                &mut best,
+                fbas,
+                monitor,
+                true,
+            );
+            best
+        };
+        combined_blocking_set.union_with(&smallest_blocking_set);
+        monitor.finish_cluster();
+    }
+    monitor.finish();
+    vec![combined_blocking_set]
+}
 impl QuorumSet {
     /// If `self` represents a symmetric quorum cluster, this function returns all minimal blocking sets of the induced FBAS.
     fn to_minimal_blocking_sets(&self, fbas: &Fbas) -> Vec<NodeIdSet> {
@@ -216,6 +405,67 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn find_smallest_blocking_set_in_correct() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json"));
+
+        let expected = Some(bitset![0, 1]);
+        let actual = find_smallest_blocking_set(&fbas);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn find_smallest_blocking_set_in_broken_trivial() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/broken_trivial.json"));
+
+        let actual = find_smallest_blocking_set(&fbas).unwrap();
+
+        assert_eq!(2, actual.len());
+        assert!(find_minimal_blocking_sets(&fbas).contains(&actual));
+    }
+
+    #[test]
+    fn find_smallest_blocking_set_in_different_consensus_clusters() {
+        let fbas = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 1, "validators": ["n1"] }
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1"] }
+            },
+            {
+                "publicKey": "n2",
+                "quorumSet": { "threshold": 2, "validators": ["n2", "n3"] }
+            },
+            {
+                "publicKey": "n3",
+                "quorumSet": { "threshold": 2, "validators": ["n2", "n3"] }
+            }
+        ]"#,
+        );
+        let actual = find_smallest_blocking_set(&fbas).unwrap();
+
+        assert_eq!(2, actual.len());
+        assert!(find_minimal_blocking_sets(&fbas).contains(&actual));
+    }
+
+    #[test]
+    fn find_smallest_blocking_set_if_no_quorum() {
+        let fbas = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1"] }
+            }
+        ]"#,
+        );
+        assert_eq!(None, find_smallest_blocking_set(&fbas));
+    }
+
     #[test]
     fn blocking_sets_of_2_of_3_quorum_set() {
         let qset = QuorumSet {
@@ -322,4 +572,45 @@ mod tests {
         );
         assert_eq!(minimal_blocking_sets, minimal_all);
     }
+
+    #[test]
+    fn find_minimal_blocking_sets_for_nodes_outside_top_tier() {
+        let fbas = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1", "n2"] }
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1", "n2"] }
+            },
+            {
+                "publicKey": "n2",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1", "n2"] }
+            },
+            {
+                "publicKey": "n3",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n3"] }
+            },
+            {
+                "publicKey": "n4",
+                "quorumSet": { "threshold": 2, "validators": ["n4", "n5"] }
+            },
+            {
+                "publicKey": "n5"
+            }
+        ]"#,
+        );
+
+        assert_eq!(
+            vec![bitset![1, 2]],
+            find_minimal_blocking_sets_for(&fbas, 0)
+        );
+        assert_eq!(
+            vec![bitset![0], bitset![1, 2]],
+            find_minimal_blocking_sets_for(&fbas, 3)
+        );
+        assert_eq!(vec![bitset![]], find_minimal_blocking_sets_for(&fbas, 4));
+    }
 }
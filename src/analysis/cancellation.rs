@@ -0,0 +1,75 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Tells long-running searches when to give up, either because a deadline has passed or because
+/// a shared cancellation token has been set. Searches that notice this stop early and return what
+/// they have found so far.
+///
+/// ## Example
+/// ```
+/// use fbas_analyzer::Cancellation;
+/// use std::sync::atomic::{AtomicBool, Ordering};
+/// use std::sync::Arc;
+/// use std::time::Duration;
+///
+/// let token = Arc::new(AtomicBool::new(false));
+/// let cancellation = Cancellation::new()
+///     .with_timeout(Duration::from_secs(3600))
+///     .with_token(token.clone());
+/// assert!(!cancellation.is_requested());
+///
+/// token.store(true, Ordering::Relaxed);
+/// assert!(cancellation.is_requested());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Cancellation {
+    deadline: Option<Instant>,
+    token: Option<Arc<AtomicBool>>,
+}
+impl Cancellation {
+    /// Never requests cancellation.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Request cancellation once `timeout` has passed, counting from now.
+    pub fn with_timeout(self, timeout: Duration) -> Self {
+        self.with_deadline(Instant::now() + timeout)
+    }
+    /// Request cancellation once `deadline` has passed.
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+    /// Request cancellation once `token` is set to `true` (e.g., from another thread).
+    pub fn with_token(mut self, token: Arc<AtomicBool>) -> Self {
+        self.token = Some(token);
+        self
+    }
+    pub fn is_requested(&self) -> bool {
+        self.token
+            .as_ref()
+            .is_some_and(|token| token.load(Ordering::Relaxed))
+            || self
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_cancellation_is_never_requested() {
+        assert!(!Cancellation::new().is_requested());
+    }
+
+    #[test]
+    fn cancellation_is_requested_after_deadline() {
+        let cancellation = Cancellation::new().with_deadline(Instant::now());
+        assert!(cancellation.is_requested());
+        let cancellation = Cancellation::new().with_timeout(Duration::from_secs(3600));
+        assert!(!cancellation.is_requested());
+    }
+}
@@ -0,0 +1,162 @@
+//! What-if simulation of cascading failures: if some nodes go offline, nodes that depend on them
+//! can no longer find a live quorum slice and stop making progress as well, which can in turn
+//! affect further nodes.
+
+use super::*;
+
+/// Outcome of a cascading failure simulation (see `simulate_cascade`).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Cascade {
+    /// Nodes that went offline in each round. The first round contains the initially failing
+    /// nodes; each later round contains the nodes that lost all of their live quorum slices
+    /// because of the preceding rounds.
+    pub rounds: Vec<NodeIdSet>,
+    /// Nodes that are still live after the cascade has come to a stop. Unless empty, they form a
+    /// quorum.
+    pub surviving_nodes: NodeIdSet,
+}
+impl Cascade {
+    /// All nodes that went offline, in any round.
+    pub fn failed_nodes(&self) -> NodeIdSet {
+        involved_nodes(&self.rounds)
+    }
+    /// Whether no quorum survived the cascade, i.e., whether no node can make progress anymore.
+    pub fn network_halts(&self) -> bool {
+        self.surviving_nodes.is_empty()
+    }
+}
+
+/// Simulates what happens if the `failing` nodes go offline. In each round, all nodes that have
+/// no quorum slice among the nodes that are still live go offline at once. The cascade stops once
+/// a round doesn't affect any further nodes; the surviving nodes are then the same as the
+/// satisfiable nodes found by `find_satisfiable_nodes`. Nodes that are unsatisfiable even if no
+/// node fails are never live and hence don't appear in any round.
+pub fn simulate_cascade(fbas: &Fbas, failing: &NodeIdSet) -> Cascade {
+    info!("Simulating cascading failure of {} nodes...", failing.len());
+    let (mut live_nodes, _) = find_satisfiable_nodes(&fbas.all_nodes(), fbas);
+    live_nodes.difference_with(failing);
+    let mut rounds = vec![failing.clone()];
+    loop {
+        let newly_failing: NodeIdSet = live_nodes
+            .iter()
+            .filter(|&node_id| !fbas.nodes[node_id].is_quorum_slice(&live_nodes))
+            .collect();
+        if newly_failing.is_empty() {
+            break;
+        }
+        live_nodes.difference_with(&newly_failing);
+        rounds.push(newly_failing);
+    }
+    info!(
+        "Cascade stopped after {} rounds; {} nodes survived.",
+        rounds.len(),
+        live_nodes.len()
+    );
+    Cascade {
+        rounds,
+        surviving_nodes: live_nodes,
+    }
+}
+
+/// Minimal quorums that survive the cascading failure of the `failing` nodes (see
+/// `simulate_cascade`), i.e., minimal quorums consisting only of surviving nodes.
+pub fn find_surviving_minimal_quorums(fbas: &Fbas, failing: &NodeIdSet) -> Vec<NodeIdSet> {
+    let surviving_nodes = simulate_cascade(fbas, failing).surviving_nodes;
+    let (fbas_shrunken, shrink_manager) = fbas.shrunken(surviving_nodes);
+    let mut minimal_quorums = shrink_manager.unshrink_sets(&find_minimal_quorums(&fbas_shrunken));
+    minimal_quorums.sort_unstable();
+    minimal_quorums.sort_by_key(|x| x.len());
+    minimal_quorums
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn chain_fbas() -> Fbas {
+        // n0, n1 and n2 require 2 out of {n0, n1, n2}; n3 requires n0 and n4 requires n3
+        Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1", "n2"] }
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1", "n2"] }
+            },
+            {
+                "publicKey": "n2",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1", "n2"] }
+            },
+            {
+                "publicKey": "n3",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n3"] }
+            },
+            {
+                "publicKey": "n4",
+                "quorumSet": { "threshold": 2, "validators": ["n3", "n4"] }
+            }
+        ]"#,
+        )
+    }
+
+    #[test]
+    fn nothing_cascades_if_nothing_fails() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct_trivial.json"));
+        let cascade = simulate_cascade(&fbas, &bitset![]);
+        assert_eq!(vec![bitset![]], cascade.rounds);
+        assert_eq!(bitset![0, 1, 2], cascade.surviving_nodes);
+        assert!(!cascade.network_halts());
+    }
+
+    #[test]
+    fn failures_cascade_in_rounds() {
+        let fbas = chain_fbas();
+        let cascade = simulate_cascade(&fbas, &bitset![0]);
+        assert_eq!(vec![bitset![0], bitset![3], bitset![4]], cascade.rounds);
+        assert_eq!(bitset![1, 2], cascade.surviving_nodes);
+        assert_eq!(bitset![0, 3, 4], cascade.failed_nodes());
+        assert!(!cascade.network_halts());
+        assert_eq!(
+            vec![bitset![1, 2]],
+            find_surviving_minimal_quorums(&fbas, &bitset![0])
+        );
+    }
+
+    #[test]
+    fn network_halts_if_no_quorum_survives() {
+        let fbas = chain_fbas();
+        let cascade = simulate_cascade(&fbas, &bitset![0, 1]);
+        assert_eq!(
+            vec![bitset![0, 1], bitset![2, 3], bitset![4]],
+            cascade.rounds
+        );
+        assert!(cascade.network_halts());
+        assert!(find_surviving_minimal_quorums(&fbas, &bitset![0, 1]).is_empty());
+    }
+
+    #[test]
+    fn unsatisfiable_nodes_dont_cascade() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json"));
+        let (_, unsatisfiable) = find_satisfiable_nodes(&fbas.all_nodes(), &fbas);
+        assert!(!unsatisfiable.is_empty());
+        let cascade = simulate_cascade(&fbas, &bitset![]);
+        assert_eq!(vec![bitset![]], cascade.rounds);
+        assert!(cascade.surviving_nodes.is_disjoint(&unsatisfiable));
+    }
+
+    #[test]
+    fn surviving_nodes_are_satisfiable_nodes() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json"));
+        let failing = bitset![0, 5, 10];
+        let mut remaining = fbas.all_nodes();
+        remaining.difference_with(&failing);
+        let (satisfiable, _) = find_satisfiable_nodes(&remaining, &fbas);
+        assert_eq!(
+            satisfiable,
+            simulate_cascade(&fbas, &failing).surviving_nodes
+        );
+    }
+}
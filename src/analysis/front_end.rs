@@ -1,19 +1,120 @@
 use super::*;
 
-use std::cell::RefCell;
+use crate::io::RawAnalysisCache;
+
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, MutexGuard, RwLock};
 
 /// Front end for the most interesting FBAS analyses.
 /// Among other things, it does ID space shrinking (which improves memory and performance when
 /// using bit sets) and caches the results of long-running computations.
+///
+/// Long-running computations can be bounded using `with_cancellation`. Results of computations
+/// that got cancelled are partial (see, e.g., `NodeIdSetVecResult::is_partial`). Their progress
+/// can be followed using `with_progress_listener`. Searches can be sped up on multi-core machines
+/// using `with_number_of_threads`. Cached results can be persisted across runs using `save_cache`
+/// and `with_cache`. How quorum intersection is checked can be chosen using
+/// `with_quorum_intersection_backend`.
+///
+/// `Analysis` is `Send + Sync`, so that one instance can be shared between threads (e.g., via an
+/// `Arc`) and, e.g., minimal blocking sets and minimal splitting sets can be computed
+/// concurrently. Concurrent requests for the same cached result compute it only once; all but
+/// one of them wait for that computation to finish.
 #[derive(Debug)]
 pub struct Analysis {
     fbas_original: Fbas,
-    fbas_shrunken: RefCell<Fbas>,
-    shrink_manager: RefCell<ShrinkManager>,
-    hqi_cache: RefCell<Option<bool>>,
-    mq_shrunken_cache: RefCell<Option<Vec<NodeIdSet>>>,
-    mbs_shrunken_cache: RefCell<Option<Vec<NodeIdSet>>>,
-    mss_shrunken_cache: RefCell<Option<Vec<NodeIdSet>>>,
+    shrunken: RwLock<Arc<ShrunkenFbas>>,
+    cancellation: Cancellation,
+    progress_listener: Option<SharedProgressListener>,
+    number_of_threads: usize,
+    qi_backend: QuorumIntersectionBackend,
+    hqi_cache: Cache<bool>,
+    mq_shrunken_cache: Cache<Vec<NodeIdSet>>,
+    mbs_shrunken_cache: Cache<Vec<NodeIdSet>>,
+    mss_shrunken_cache: Cache<Vec<NodeIdSet>>,
+}
+/// The FBAS in the node ID space that cached results refer to. Gets replaced when shrinking the
+/// ID space further, but never changed in place, so that running computations can keep using
+/// their own copy.
+#[derive(Debug)]
+struct ShrunkenFbas {
+    fbas: Fbas,
+    shrink_manager: ShrinkManager,
+}
+/// A cached result and whether its computation got cancelled, i.e., whether it is partial.
+/// Whoever computes the result holds `computing` for the duration of the computation, so that
+/// concurrent requests wait instead of computing the same thing again.
+///
+/// Locks are always acquired in the order `computing` -> `Analysis::shrunken` -> `value`, and
+/// `Analysis::shrunken` and `value` are never held while computing something.
+#[derive(Debug, Default)]
+struct Cache<R> {
+    value: Mutex<Option<(R, bool)>>,
+    computing: Mutex<()>,
+}
+/// A result in terms of the node IDs of `shrunken`, and whether it is partial.
+struct ShrunkenResult<R> {
+    payload: R,
+    partial: bool,
+    shrunken: Arc<ShrunkenFbas>,
+}
+/// Cached results that can be translated between shrunken ID spaces.
+trait Reshrink {
+    fn reshrink(
+        self,
+        new_shrink_manager: &ShrinkManager,
+        old_shrink_manager: &ShrinkManager,
+    ) -> Self;
+}
+impl Reshrink for bool {
+    fn reshrink(self, _: &ShrinkManager, _: &ShrinkManager) -> Self {
+        self
+    }
+}
+impl Reshrink for Vec<NodeIdSet> {
+    fn reshrink(
+        self,
+        new_shrink_manager: &ShrinkManager,
+        old_shrink_manager: &ShrinkManager,
+    ) -> Self {
+        new_shrink_manager.reshrink_sets(&self, old_shrink_manager)
+    }
+}
+
+/// Complete results computed by an `Analysis`, for reuse by another `Analysis` of an FBAS with
+/// the same standard form hash (see `Analysis::cached_results` and
+/// `Analysis::with_cached_results`). In-memory counterpart of the files written by
+/// `Analysis::save_cache`.
+#[derive(Clone, Debug)]
+pub struct AnalysisCache(RawAnalysisCache);
+impl AnalysisCache {
+    /// Standard form hash of the FBAS the results belong to (see `Fbas::standard_form_hash`).
+    pub fn fbas_hash(&self) -> &str {
+        &self.0.fbas_hash
+    }
+}
+
+/// How `Analysis::has_quorum_intersection` determines quorum intersection.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QuorumIntersectionBackend {
+    /// Find all minimal quorums and check if each two of them intersect. The minimal quorums are
+    /// needed for other analyses anyway, so this is the default.
+    MinimalQuorums,
+    /// Look for two non-intersecting quorums using a SAT solver (see
+    /// `find_nonintersecting_quorums_via_sat`). Doesn't get cancelled.
+    Sat,
+}
+impl FromStr for QuorumIntersectionBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "minimal-quorums" => Ok(QuorumIntersectionBackend::MinimalQuorums),
+            "sat" => Ok(QuorumIntersectionBackend::Sat),
+            _ => Err(format!("unknown quorum intersection backend: {}", s)),
+        }
+    }
 }
 impl Analysis {
     /// Start a new `Analysis`
@@ -30,13 +131,149 @@ impl Analysis {
         );
         Analysis {
             fbas_original: fbas.clone(),
-            fbas_shrunken: RefCell::new(fbas_shrunken),
-            shrink_manager: RefCell::new(shrink_manager),
-            hqi_cache: RefCell::new(None),
-            mq_shrunken_cache: RefCell::new(None),
-            mbs_shrunken_cache: RefCell::new(None),
-            mss_shrunken_cache: RefCell::new(None),
+            shrunken: RwLock::new(Arc::new(ShrunkenFbas {
+                fbas: fbas_shrunken,
+                shrink_manager,
+            })),
+            cancellation: Cancellation::new(),
+            progress_listener: None,
+            number_of_threads: 1,
+            qi_backend: QuorumIntersectionBackend::MinimalQuorums,
+            hqi_cache: Cache::default(),
+            mq_shrunken_cache: Cache::default(),
+            mbs_shrunken_cache: Cache::default(),
+            mss_shrunken_cache: Cache::default(),
+        }
+    }
+    /// Start a new `Analysis` of the FBAS in which each grouping (organization, ISP, country) is
+    /// one node (see `Groupings::merged_fbas`), so that searches run over groupings instead of
+    /// over nodes. Results refer to the node IDs of this merged FBAS, which is available via
+    /// `fbas`; its nodes are named after their groupings, so that, e.g.,
+    /// `into_pretty_vec_vec(analysis.fbas(), None)` yields organization names.
+    ///
+    /// If all nodes of a grouping have the same quorum set (after merging), as is usual for
+    /// organizations, minimal quorums, minimal blocking sets and the top tier are exactly those
+    /// found via `Analysis::new` and merged afterwards (via `merged_by_group` and
+    /// `minimal_sets`), just without searching through node-level sets first. Otherwise they are
+    /// an approximation, in which each grouping behaves like a node using the quorum set that is
+    /// most common among its nodes.
+    ///
+    /// Quorum intersection and minimal splitting sets can come out wrong even then: two disjoint
+    /// node-level quorums that contain different nodes of the same grouping intersect once the
+    /// grouping is one node, so the grouped analysis can report quorum intersection for an FBAS
+    /// that lacks it. Use `Analysis::new` to check these.
+    ///
+    /// Node sets passed to the analysis, e.g., the faulty nodes for `intact_nodes`, refer to the
+    /// merged FBAS as well, i.e., groupings can only fail as a whole.
+    ///
+    /// Panics if `groupings` doesn't belong to `fbas`.
+    pub fn new_grouped(fbas: &Fbas, groupings: &Groupings) -> Self {
+        assert!(
+            fbas == groupings.fbas,
+            "Groupings belong to a different FBAS!"
+        );
+        debug!(
+            "Merging FBAS of size {} into {} groupings (and ungrouped nodes)...",
+            fbas.number_of_nodes(),
+            groupings.number_of_groupings()
+        );
+        Self::new(&groupings.merged_fbas())
+    }
+    /// Start a new `Analysis`, reusing the results stored in `cache_path` by `save_cache` if they
+    /// belong to an FBAS with the same standard form hash (see `Fbas::standard_form_hash`).
+    /// Behaves like `Analysis::new` if there is no such file or if it belongs to another FBAS.
+    pub fn with_cache(fbas: &Fbas, cache_path: &Path) -> Result<Self, Error> {
+        if !cache_path.exists() {
+            info!("No analysis cache found at {:?}.", cache_path);
+            return Ok(Self::new(fbas));
         }
+        let cache = RawAnalysisCache::from_json_file(cache_path)?;
+        info!("Read analysis cache from {:?}.", cache_path);
+        Self::with_cached_results(fbas, &AnalysisCache(cache))
+    }
+    /// Start a new `Analysis`, reusing `cached_results` (see `cached_results`) if they belong to
+    /// an FBAS with the same standard form hash (see `Fbas::standard_form_hash`). Behaves like
+    /// `Analysis::new` if they belong to another FBAS.
+    pub fn with_cached_results(fbas: &Fbas, cached_results: &AnalysisCache) -> Result<Self, Error> {
+        let analysis = Self::new(fbas);
+        if cached_results.fbas_hash() != fbas.standard_form_hash() {
+            info!("Cached analysis results belong to a different FBAS; ignoring them.");
+            return Ok(analysis);
+        }
+        analysis.restore_cache(cached_results.0.clone())?;
+        info!("Restored cached analysis results.");
+        Ok(analysis)
+    }
+    /// Store all results computed so far in `cache_path` (overwriting it), for reuse via
+    /// `with_cache`. Partial results are not stored.
+    pub fn save_cache(&self, cache_path: &Path) -> Result<(), Error> {
+        self.cached_results().0.to_json_file(cache_path)
+    }
+    /// All results computed so far, for reuse via `with_cached_results`. Partial results are left
+    /// out.
+    pub fn cached_results(&self) -> AnalysisCache {
+        // holding this makes sure that no cached result gets reshrunk while we read it
+        let shrunken = self.shrunken.read().unwrap();
+        let complete_sets = |cache: &Cache<Vec<NodeIdSet>>| match &*cache.value() {
+            Some((sets, false)) => Some(sets.iter().map(|set| set.iter().collect()).collect()),
+            _ => None,
+        };
+        AnalysisCache(RawAnalysisCache {
+            fbas_hash: self.fbas_original.standard_form_hash(),
+            shrunken_public_keys: shrunken
+                .shrink_manager
+                .unshrink_table()
+                .iter()
+                .map(|&node_id| self.fbas_original.nodes[node_id].public_key.clone())
+                .collect(),
+            has_quorum_intersection: match *self.hqi_cache.value() {
+                Some((has_quorum_intersection, false)) => Some(has_quorum_intersection),
+                _ => None,
+            },
+            minimal_quorums: complete_sets(&self.mq_shrunken_cache),
+            minimal_blocking_sets: complete_sets(&self.mbs_shrunken_cache),
+            minimal_splitting_sets: complete_sets(&self.mss_shrunken_cache),
+        })
+    }
+    /// Stop long-running computations early once `cancellation` is requested. Cancellation is
+    /// assumed to be final; all computations started after it is requested return immediately.
+    pub fn with_cancellation(mut self, cancellation: Cancellation) -> Self {
+        self.cancellation = cancellation;
+        self
+    }
+    /// Report the progress of long-running searches (for minimal quorums, blocking sets, etc.) to
+    /// `listener`.
+    pub fn with_progress_listener(
+        mut self,
+        listener: impl ProgressListener + Send + Sync + 'static,
+    ) -> Self {
+        self.progress_listener = Some(SharedProgressListener(Arc::new(listener)));
+        self
+    }
+    /// Split searches for minimal quorums, blocking sets and splitting sets into work items that
+    /// are processed by `number_of_threads` threads. This doesn't change any results, but progress
+    /// is then only reported after each searched consensus cluster.
+    pub fn with_number_of_threads(mut self, number_of_threads: usize) -> Self {
+        self.number_of_threads = number_of_threads;
+        self
+    }
+    /// Determine quorum intersection using `backend` (see `has_quorum_intersection`).
+    pub fn with_quorum_intersection_backend(mut self, backend: QuorumIntersectionBackend) -> Self {
+        self.qi_backend = backend;
+        self
+    }
+    pub fn quorum_intersection_backend(&self) -> QuorumIntersectionBackend {
+        self.qi_backend
+    }
+    /// The analyzed FBAS, to whose node IDs all results refer. For analyses started via
+    /// `new_grouped`, this is the merged FBAS.
+    pub fn fbas(&self) -> &Fbas {
+        &self.fbas_original
+    }
+    /// Whether cancellation has been requested, i.e., whether results computed from now on will
+    /// be partial.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation.is_requested()
     }
     /// Nodes in the analyzed FBAS - not filtered by relevance.
     pub fn all_nodes(&self) -> NodeIdSetResult {
@@ -52,41 +289,175 @@ impl Analysis {
     pub fn unsatisfiable_nodes(&self) -> NodeIdSetResult {
         self.make_unshrunken_set_result(self.fbas_original.unsatisfiable_nodes())
     }
+    /// Nodes that stay safe and live no matter what the `faulty` nodes do (see
+    /// `find_intact_nodes`).
+    pub fn intact_nodes(&self, faulty: &NodeIdSet) -> NodeIdSetResult {
+        self.make_unshrunken_set_result(find_intact_nodes(&self.fbas_original, faulty))
+    }
+    /// Nodes that aren't intact given the `faulty` nodes (see `find_befouled_nodes`).
+    pub fn befouled_nodes(&self, faulty: &NodeIdSet) -> NodeIdSetResult {
+        self.make_unshrunken_set_result(find_befouled_nodes(&self.fbas_original, faulty))
+    }
+    /// Whether the `faulty` nodes can't cause a fork (see `has_quorum_intersection_despite`).
+    pub fn has_quorum_intersection_despite(&self, faulty: &NodeIdSet) -> bool {
+        has_quorum_intersection_despite(&self.fbas_original, faulty)
+    }
+    /// Whether the `faulty` nodes can't block progress (see `has_quorum_availability_despite`).
+    pub fn has_quorum_availability_despite(&self, faulty: &NodeIdSet) -> bool {
+        has_quorum_availability_despite(&self.fbas_original, faulty)
+    }
+    /// Nodes that go offline in each round if the `failing` nodes go offline, starting with the
+    /// `failing` nodes themselves (see `simulate_cascade`).
+    pub fn cascade_rounds(&self, failing: &NodeIdSet) -> NodeIdSetVecResult {
+        self.make_unshrunken_set_vec_result(
+            simulate_cascade(&self.fbas_original, failing).rounds,
+            false,
+        )
+    }
+    /// Nodes that are still live after the cascading failure of the `failing` nodes.
+    pub fn surviving_nodes(&self, failing: &NodeIdSet) -> NodeIdSetResult {
+        self.make_unshrunken_set_result(
+            simulate_cascade(&self.fbas_original, failing).surviving_nodes,
+        )
+    }
+    /// Minimal quorums that survive the cascading failure of the `failing` nodes (see
+    /// `find_surviving_minimal_quorums`).
+    pub fn surviving_minimal_quorums(&self, failing: &NodeIdSet) -> NodeIdSetVecResult {
+        self.make_unshrunken_set_vec_result(
+            find_surviving_minimal_quorums(&self.fbas_original, failing),
+            false,
+        )
+    }
+    /// Whether no node can make progress anymore after the cascading failure of the `failing`
+    /// nodes.
+    pub fn cascade_halts_network(&self, failing: &NodeIdSet) -> bool {
+        simulate_cascade(&self.fbas_original, failing).network_halts()
+    }
+    /// Minimal sets of nodes whose failure can neither cause a fork nor block progress for the
+    /// remaining nodes (see `find_minimal_dsets`).
+    pub fn minimal_dsets(&self) -> NodeIdSetVecResult {
+        let minimal_dsets =
+            find_minimal_dsets_monitored(&self.fbas_original, &mut self.monitor("minimal DSets"));
+        self.make_unshrunken_set_vec_result(minimal_dsets, self.is_cancelled())
+    }
+    /// Whether `node_set` is a DSet (see `is_dset`).
+    pub fn is_dset(&self, node_set: &NodeIdSet) -> bool {
+        is_dset(&self.fbas_original, node_set)
+    }
     /// Regular quorum intersection check via finding all minimal quorums.
     /// Algorithm inspired by [Lachowski 2019](https://arxiv.org/abs/1902.06493)).
+    /// If the search for minimal quorums got cancelled, `true` only means that all minimal quorums
+    /// found so far intersect.
+    /// Uses `has_quorum_intersection_via_sat` instead if `QuorumIntersectionBackend::Sat` was
+    /// chosen via `with_quorum_intersection_backend`.
     pub fn has_quorum_intersection(&self) -> bool {
         self.has_quorum_intersection_from_shrunken()
     }
     /// Quorum intersection check that works without enumerating all minimal quorums.
+    /// If the search got cancelled, `true` only means that no non-intersecting quorums were found
+    /// in time.
     pub fn has_quorum_intersection_via_alternative_check(
         &self,
     ) -> (bool, Option<NodeIdSetVecResult>) {
-        if let Some(quorums) = find_nonintersecting_quorums(&self.fbas_shrunken.borrow()) {
+        let shrunken = self.shrunken_fbas();
+        if let Some(quorums) = find_nonintersecting_quorums_monitored(
+            &shrunken.fbas,
+            &mut self.monitor("non-intersecting quorums"),
+        ) {
             assert!(quorums[0].is_disjoint(&quorums[1]));
             (
                 false,
-                Some(self.make_shrunken_set_vec_result(quorums.to_vec())),
+                Some(self.make_shrunken_set_vec_result(quorums.to_vec(), false, &shrunken)),
             )
         } else {
             (true, None)
         }
     }
+    /// Quorum intersection check that asks a SAT solver for two non-intersecting quorums, which
+    /// are returned if they exist (an empty list is returned if there are no quorums at all).
+    /// Often much faster than `has_quorum_intersection` for large, asymmetric FBASs. Ignores
+    /// cancellation.
+    pub fn has_quorum_intersection_via_sat(&self) -> (bool, Option<NodeIdSetVecResult>) {
+        let shrunken = self.shrunken_fbas();
+        let quorums = find_nonintersecting_quorums_via_sat(&shrunken.fbas);
+        let has_quorum_intersection = quorums.is_none();
+        *self.hqi_cache.value() = Some((has_quorum_intersection, false));
+        (
+            has_quorum_intersection,
+            quorums.map(|quorums| self.make_shrunken_set_vec_result(quorums, false, &shrunken)),
+        )
+    }
     /// Minimal quorums - no proper subset of any of these node sets is a quorum.
     pub fn minimal_quorums(&self) -> NodeIdSetVecResult {
-        self.make_shrunken_set_vec_result(self.minimal_quorums_shrunken())
+        self.make_shrunken_set_vec_result_from(self.minimal_quorums_shrunken())
     }
     /// Minimal blocking sets - minimal indispensable sets for global liveness.
     pub fn minimal_blocking_sets(&self) -> NodeIdSetVecResult {
-        self.make_shrunken_set_vec_result(self.minimal_blocking_sets_shrunken())
+        self.make_shrunken_set_vec_result_from(self.minimal_blocking_sets_shrunken())
     }
     /// Minimal splitting sets - minimal indispensable sets for safety.
     pub fn minimal_splitting_sets(&self) -> NodeIdSetVecResult {
-        self.make_shrunken_set_vec_result(self.minimal_splitting_sets_shrunken())
+        self.make_shrunken_set_vec_result_from(self.minimal_splitting_sets_shrunken())
+    }
+    /// Minimal quorums containing `node` - no proper subset of any of these node sets is a quorum
+    /// containing `node` (see `find_minimal_quorums_containing`). Not cached.
+    pub fn minimal_quorums_containing(&self, node: NodeId) -> NodeIdSetVecResult {
+        let minimal_quorums = find_minimal_quorums_containing_monitored(
+            &self.fbas_original,
+            node,
+            &mut self.monitor("minimal quorums containing node"),
+        );
+        self.make_unshrunken_set_vec_result(minimal_quorums, self.is_cancelled())
+    }
+    /// Minimal blocking sets for `node` - minimal sets of other nodes that can block `node` (see
+    /// `find_minimal_blocking_sets_for`). Not cached.
+    pub fn minimal_blocking_sets_for(&self, node: NodeId) -> NodeIdSetVecResult {
+        let minimal_blocking_sets = find_minimal_blocking_sets_for_monitored(
+            &self.fbas_original,
+            node,
+            &mut self.monitor("minimal blocking sets for node"),
+        );
+        self.make_unshrunken_set_vec_result(minimal_blocking_sets, self.is_cancelled())
+    }
+    /// One of the smallest minimal blocking sets, found via branch and bound. Much faster than
+    /// `minimal_blocking_sets` if only the size of the smallest blocking set is of interest.
+    /// `None` if there are no quorums (and hence nothing to block).
+    pub fn smallest_blocking_set(&self) -> Option<NodeIdSetResult> {
+        let shrunken = self.shrunken_fbas();
+        let smallest_blocking_set = find_smallest_blocking_set_monitored(
+            &shrunken.fbas,
+            &mut self.monitor("smallest blocking set"),
+        );
+        let partial = self.is_cancelled();
+        smallest_blocking_set
+            .map(|blocking_set| self.make_shrunken_set_result(blocking_set, partial, &shrunken))
+    }
+    /// One of the smallest minimal splitting sets, found via branch and bound. Much faster than
+    /// `minimal_splitting_sets` if only the size of the smallest splitting set is of interest.
+    /// Still needs the minimal quorums. `None` if there is no splitting set.
+    pub fn smallest_splitting_set(&self) -> Option<NodeIdSetResult> {
+        if self.has_quorum_intersection() {
+            let minimal_quorums = self.minimal_quorums_shrunken();
+            let shrunken = minimal_quorums.shrunken;
+            let smallest_splitting_set = find_smallest_splitting_set_monitored(
+                &shrunken.fbas,
+                &minimal_quorums.payload,
+                &mut self.monitor("smallest splitting set"),
+            );
+            let partial = self.is_cancelled();
+            smallest_splitting_set.map(|splitting_set| {
+                self.make_shrunken_set_result(splitting_set, partial, &shrunken)
+            })
+        } else {
+            let partial = is_partial(&self.hqi_cache);
+            Some(self.make_shrunken_set_result(bitset![], partial, &self.shrunken_fbas()))
+        }
     }
     /// Top tier - the set of nodes exclusively relevant when determining minimal blocking sets and
     /// minimal splitting sets.
     pub fn top_tier(&self) -> NodeIdSetResult {
-        self.make_shrunken_set_result(self.top_tier_shrunken())
+        let top_tier = self.top_tier_shrunken();
+        self.make_shrunken_set_result(top_tier.payload, top_tier.partial, &top_tier.shrunken)
     }
     /// If the top tier is symmetric, i.e., each two top-tier nodes have the same quorum set,
     /// return the top tier's common quorum set. Else return `None`.
@@ -100,39 +471,56 @@ impl Analysis {
     }
 
     fn has_quorum_intersection_from_shrunken(&self) -> bool {
+        if self.qi_backend == QuorumIntersectionBackend::Sat {
+            let _computing = self.hqi_cache.lock_computing();
+            let cached_hqi = self.hqi_cache.value().map(|(hqi, _)| hqi);
+            return cached_hqi.unwrap_or_else(|| self.has_quorum_intersection_via_sat().0);
+        }
         self.cached_computation(
             &self.hqi_cache,
-            || {
-                let quorums = self.minimal_quorums_shrunken();
+            |_| {
+                let quorums = self.minimal_quorums_shrunken().payload;
                 !quorums.is_empty() && all_intersect(&quorums)
             },
             "has quorum intersection",
             false,
         )
+        .payload
     }
-    fn minimal_quorums_shrunken(&self) -> Vec<NodeIdSet> {
-        self.cached_computation_from_fbas_shrunken(
+    fn minimal_quorums_shrunken(&self) -> ShrunkenResult<Vec<NodeIdSet>> {
+        self.cached_computation(
             &self.mq_shrunken_cache,
-            find_minimal_quorums,
+            |shrunken| {
+                find_minimal_quorums_monitored(&shrunken.fbas, &mut self.monitor("minimal quorums"))
+            },
             "minimal quorums",
             true,
         )
     }
-    fn minimal_blocking_sets_shrunken(&self) -> Vec<NodeIdSet> {
-        self.cached_computation_from_fbas_shrunken(
+    fn minimal_blocking_sets_shrunken(&self) -> ShrunkenResult<Vec<NodeIdSet>> {
+        self.cached_computation(
             &self.mbs_shrunken_cache,
-            find_minimal_blocking_sets,
+            |shrunken| {
+                find_minimal_blocking_sets_monitored(
+                    &shrunken.fbas,
+                    &mut self.monitor("minimal blocking sets"),
+                )
+            },
             "minimal blocking sets",
             true,
         )
     }
-    fn minimal_splitting_sets_shrunken(&self) -> Vec<NodeIdSet> {
+    fn minimal_splitting_sets_shrunken(&self) -> ShrunkenResult<Vec<NodeIdSet>> {
         let minimal_quorums = self.minimal_quorums_shrunken();
-        self.cached_computation_from_fbas_shrunken(
+        self.cached_computation(
             &self.mss_shrunken_cache,
-            |fbas| {
+            |shrunken| {
                 if self.has_quorum_intersection() {
-                    find_minimal_splitting_sets(fbas, &minimal_quorums)
+                    find_minimal_splitting_sets_monitored(
+                        &shrunken.fbas,
+                        &minimal_quorums.payload_for(shrunken),
+                        &mut self.monitor("minimal splitting sets"),
+                    )
                 } else {
                     vec![bitset![]]
                 }
@@ -141,105 +529,260 @@ impl Analysis {
             false,
         )
     }
-    fn top_tier_shrunken(&self) -> NodeIdSet {
+    fn top_tier_shrunken(&self) -> ShrunkenResult<NodeIdSet> {
         // The top tier is defined as either the union of all minimal quorums but can also be found
         // by forming the union of all minimal blocking sets.
-        if self.mq_shrunken_cache.borrow().is_some() || self.mbs_shrunken_cache.borrow().is_none() {
-            involved_nodes(&self.minimal_quorums_shrunken())
+        let defining_sets = if self.mq_shrunken_cache.value().is_some()
+            || self.mbs_shrunken_cache.value().is_none()
+        {
+            self.minimal_quorums_shrunken()
         } else {
-            involved_nodes(&self.minimal_blocking_sets_shrunken())
+            self.minimal_blocking_sets_shrunken()
+        };
+        ShrunkenResult {
+            payload: involved_nodes(&defining_sets.payload),
+            partial: defining_sets.partial,
+            shrunken: defining_sets.shrunken,
         }
     }
 
-    fn cached_computation_from_fbas_shrunken<R, F>(
-        &self,
-        cache: &RefCell<Option<R>>,
-        computation: F,
-        log_name: &str,
-        result_defines_top_tier: bool,
-    ) -> R
-    where
-        R: Clone,
-        F: Fn(&Fbas) -> R,
-    {
-        self.cached_computation(
-            cache,
-            || computation(&self.fbas_shrunken.borrow()),
-            log_name,
-            result_defines_top_tier,
+    fn monitor(&self, search: &'static str) -> SearchMonitor<'_> {
+        SearchMonitor::new(
+            search,
+            &self.cancellation,
+            self.progress_listener
+                .as_ref()
+                .map(|listener| listener.0.as_ref() as &dyn ProgressListener),
         )
+        .with_number_of_threads(self.number_of_threads)
+    }
+    fn shrunken_fbas(&self) -> Arc<ShrunkenFbas> {
+        Arc::clone(&self.shrunken.read().unwrap())
     }
+
     fn cached_computation<R, F>(
         &self,
-        cache: &RefCell<Option<R>>,
+        cache: &Cache<R>,
         computation: F,
         log_name: &str,
         result_defines_top_tier: bool,
-    ) -> R
+    ) -> ShrunkenResult<R>
     where
-        R: Clone,
-        F: Fn() -> R,
+        R: Clone + Reshrink,
+        F: Fn(&Arc<ShrunkenFbas>) -> R,
     {
-        let cache_is_empty = cache.borrow().is_none();
-        if cache_is_empty {
-            let should_shrink = if result_defines_top_tier {
-                // top tier not defined yet
-                self.mq_shrunken_cache.borrow().is_none()
-                    && self.mbs_shrunken_cache.borrow().is_none()
-            } else {
-                false
-            };
-
-            info!("Computing {}...", log_name);
-            let result = computation();
-            cache.replace(Some(result));
-            if should_shrink {
-                self.shrink_id_space_to_top_tier();
-            }
-        } else {
+        let _computing = cache.lock_computing();
+        if let Some(cached) = self.read_cache(cache) {
             info!("Using cached {}.", log_name);
+            return cached;
         }
-        cache.borrow().clone().unwrap()
+        info!("Computing {}...", log_name);
+        let computed_on = self.shrunken_fbas();
+        let mut result = computation(&computed_on);
+        // partial results are cached as well, so that repeated queries stay consistent
+        let partial = self.cancellation.is_requested();
+        if partial {
+            warn!(
+                "Computation of {} got cancelled; results are partial.",
+                log_name
+            );
+        }
+
+        let mut shrunken = self.shrunken.write().unwrap();
+        if !Arc::ptr_eq(&shrunken, &computed_on) {
+            // another computation shrank the ID space while we were busy
+            result = result.reshrink(&shrunken.shrink_manager, &computed_on.shrink_manager);
+        }
+        // top tier not defined yet, neither by us nor by a concurrent computation
+        let should_shrink = result_defines_top_tier
+            && !partial
+            && self.mq_shrunken_cache.value().is_none()
+            && self.mbs_shrunken_cache.value().is_none();
+        *cache.value() = Some((result, partial));
+        if should_shrink {
+            self.shrink_id_space_to_top_tier(&mut shrunken);
+        }
+        drop(shrunken);
+        self.read_cache(cache).unwrap()
+    }
+    fn read_cache<R: Clone>(&self, cache: &Cache<R>) -> Option<ShrunkenResult<R>> {
+        let shrunken = self.shrunken.read().unwrap();
+        cache
+            .value()
+            .clone()
+            .map(|(payload, partial)| ShrunkenResult {
+                payload,
+                partial,
+                shrunken: Arc::clone(&shrunken),
+            })
     }
 
-    #[rustfmt::skip]
-    fn shrink_id_space_to_top_tier(&self) {
+    /// Use the shrunken node IDs and results from `cache`, translating them to the node IDs of
+    /// our FBAS via their public keys.
+    fn restore_cache(&self, cache: RawAnalysisCache) -> Result<(), Error> {
+        let relevant_nodes = self.fbas_original.relevant_nodes();
+        let cached_unshrink_table = cache
+            .shrunken_public_keys
+            .iter()
+            .map(
+                |public_key| match self.fbas_original.get_node_id(public_key) {
+                    Some(node_id) if relevant_nodes.contains(node_id) => Ok(node_id),
+                    Some(_) => Err(Error::InvalidAnalysisCache(format!(
+                        "public key {} belongs to an irrelevant node",
+                        public_key
+                    ))),
+                    None => Err(Error::InvalidAnalysisCache(format!(
+                        "unknown public key {}",
+                        public_key
+                    ))),
+                },
+            )
+            .collect::<Result<Vec<NodeId>, Error>>()?;
+        let (fbas_shrunken, shrink_manager) = Fbas::shrunken(
+            &self.fbas_original,
+            cached_unshrink_table.iter().copied().collect(),
+        );
+        let restore_sets = |cached_sets: Option<Vec<Vec<NodeId>>>| {
+            cached_sets
+                .map(|cached_sets| {
+                    let cached_sets: Vec<NodeIdSet> = cached_sets
+                        .into_iter()
+                        .map(|cached_set| cached_set.into_iter().collect())
+                        .collect();
+                    if cached_sets
+                        .iter()
+                        .flat_map(|set| set.iter())
+                        .any(|node_id| node_id >= cached_unshrink_table.len())
+                    {
+                        Err(Error::InvalidAnalysisCache("unknown node ID".to_string()))
+                    } else {
+                        let original_sets = unshrink_sets(&cached_sets, &cached_unshrink_table);
+                        let mut sets = shrink_manager.shrink_sets(&original_sets);
+                        // node IDs might have changed, so we restore the usual order
+                        sets.sort_unstable();
+                        sets.sort_by_key(|x| x.len());
+                        Ok(Some((sets, false)))
+                    }
+                })
+                .unwrap_or(Ok(None))
+        };
+        let mq_shrunken_cache = restore_sets(cache.minimal_quorums)?;
+        let mbs_shrunken_cache = restore_sets(cache.minimal_blocking_sets)?;
+        let mss_shrunken_cache = restore_sets(cache.minimal_splitting_sets)?;
+        *self.shrunken.write().unwrap() = Arc::new(ShrunkenFbas {
+            fbas: fbas_shrunken,
+            shrink_manager,
+        });
+        *self.hqi_cache.value() = cache
+            .has_quorum_intersection
+            .map(|has_quorum_intersection| (has_quorum_intersection, false));
+        *self.mq_shrunken_cache.value() = mq_shrunken_cache;
+        *self.mbs_shrunken_cache.value() = mbs_shrunken_cache;
+        *self.mss_shrunken_cache.value() = mss_shrunken_cache;
+        Ok(())
+    }
+
+    /// Expects to be called with the write lock on `self.shrunken` held and at least one of the
+    /// minimal quorums and minimal blocking sets cached.
+    fn shrink_id_space_to_top_tier(&self, shrunken: &mut Arc<ShrunkenFbas>) {
         debug!("Shrinking FBAS again, to top tier (for performance)...",);
-        let top_tier_original = self
-            .shrink_manager
-            .borrow()
-            .unshrink_set(&self.top_tier_shrunken());
+        let mut mq_shrunken_cache = self.mq_shrunken_cache.value();
+        let mut mbs_shrunken_cache = self.mbs_shrunken_cache.value();
+        let top_tier_shrunken = match (&*mq_shrunken_cache, &*mbs_shrunken_cache) {
+            (Some((defining_sets, _)), _) | (None, Some((defining_sets, _))) => {
+                involved_nodes(defining_sets)
+            }
+            (None, None) => unreachable!("top tier not defined yet"),
+        };
+        let top_tier_original = shrunken.shrink_manager.unshrink_set(&top_tier_shrunken);
         let (new_fbas_shrunken, new_shrink_manager) =
             Fbas::shrunken(&self.fbas_original, top_tier_original);
         debug!(
             "Shrank to an FBAS of size {} (from size {}).",
             new_fbas_shrunken.number_of_nodes(),
-            self.fbas_shrunken.borrow().number_of_nodes(),
+            shrunken.fbas.number_of_nodes(),
         );
 
         debug!("Fixing previously cached values...");
-        assert!(
-            self.mq_shrunken_cache.borrow().is_none() || self.mbs_shrunken_cache.borrow().is_none()
-        );
-        let mq_shrunken_cache = self.mq_shrunken_cache.borrow().clone().map(|mq_shrunken| {
-            new_shrink_manager.reshrink_sets(&mq_shrunken, &self.shrink_manager.borrow())
-        });
-        let mbs_shrunken_cache = self.mbs_shrunken_cache.borrow().clone().map(|mbs_shrunken| {
-            new_shrink_manager.reshrink_sets(&mbs_shrunken, &self.shrink_manager.borrow())
+        for cache in [&mut *mq_shrunken_cache, &mut *mbs_shrunken_cache] {
+            if let Some((sets, partial)) = cache.take() {
+                *cache = Some((
+                    sets.reshrink(&new_shrink_manager, &shrunken.shrink_manager),
+                    partial,
+                ));
+            }
+        }
+        *shrunken = Arc::new(ShrunkenFbas {
+            fbas: new_fbas_shrunken,
+            shrink_manager: new_shrink_manager,
         });
-        self.fbas_shrunken.replace(new_fbas_shrunken);
-        self.shrink_manager.replace(new_shrink_manager);
-        self.mq_shrunken_cache.replace(mq_shrunken_cache);
-        self.mbs_shrunken_cache.replace(mbs_shrunken_cache);
     }
 
     fn make_unshrunken_set_result(&self, payload: NodeIdSet) -> NodeIdSetResult {
         NodeIdSetResult::new(payload, None)
     }
-    fn make_shrunken_set_result(&self, payload: NodeIdSet) -> NodeIdSetResult {
-        NodeIdSetResult::new(payload, Some(&self.shrink_manager.borrow()))
+    fn make_unshrunken_set_vec_result(
+        &self,
+        payload: Vec<NodeIdSet>,
+        partial: bool,
+    ) -> NodeIdSetVecResult {
+        let mut result = NodeIdSetVecResult::new(payload, None);
+        result.partial = partial;
+        result
+    }
+    fn make_shrunken_set_result(
+        &self,
+        payload: NodeIdSet,
+        partial: bool,
+        shrunken: &ShrunkenFbas,
+    ) -> NodeIdSetResult {
+        let mut result = NodeIdSetResult::new(payload, Some(&shrunken.shrink_manager));
+        result.partial = partial;
+        result
+    }
+    fn make_shrunken_set_vec_result(
+        &self,
+        payload: Vec<NodeIdSet>,
+        partial: bool,
+        shrunken: &ShrunkenFbas,
+    ) -> NodeIdSetVecResult {
+        let mut result = NodeIdSetVecResult::new(payload, Some(&shrunken.shrink_manager));
+        result.partial = partial;
+        result
+    }
+    fn make_shrunken_set_vec_result_from(
+        &self,
+        result: ShrunkenResult<Vec<NodeIdSet>>,
+    ) -> NodeIdSetVecResult {
+        self.make_shrunken_set_vec_result(result.payload, result.partial, &result.shrunken)
+    }
+}
+
+impl<R> Cache<R> {
+    fn value(&self) -> MutexGuard<'_, Option<(R, bool)>> {
+        self.value.lock().unwrap()
+    }
+    fn lock_computing(&self) -> MutexGuard<'_, ()> {
+        // a panicking computation leaves nothing behind that we would need to clean up
+        self.computing
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
     }
-    fn make_shrunken_set_vec_result(&self, payload: Vec<NodeIdSet>) -> NodeIdSetVecResult {
-        NodeIdSetVecResult::new(payload, Some(&self.shrink_manager.borrow()))
+}
+
+impl<R: Clone + Reshrink> ShrunkenResult<R> {
+    /// The payload in terms of the node IDs of `shrunken`.
+    fn payload_for(&self, shrunken: &Arc<ShrunkenFbas>) -> R {
+        if Arc::ptr_eq(&self.shrunken, shrunken) {
+            self.payload.clone()
+        } else {
+            self.payload
+                .clone()
+                .reshrink(&shrunken.shrink_manager, &self.shrunken.shrink_manager)
+        }
     }
 }
+
+fn is_partial<R>(cache: &Cache<R>) -> bool {
+    cache.value().as_ref().is_some_and(|(_, partial)| *partial)
+}
@@ -0,0 +1,276 @@
+//! Analyses relative to a set of faulty (ill-behaved) nodes, following the definitions of the
+//! [SCP paper](https://www.stellar.org/papers/stellar-consensus-protocol). Deleting a set of nodes
+//! `B` from an FBAS means removing `B` from all quorum slices (so that the remaining nodes no
+//! longer depend on `B`).
+
+use super::*;
+
+/// Intact nodes given that the `faulty` nodes are ill-behaved: nodes that are guaranteed to stay
+/// safe and live, no matter what the faulty nodes do. A set of nodes `I` is intact if it is a
+/// quorum of well-behaved nodes that enjoys quorum intersection after deleting all other nodes;
+/// a node is intact if it is part of some intact set. Equivalently, the intact nodes are the
+/// complement of the smallest DSet containing `faulty` (if the FBAS enjoys quorum intersection).
+pub fn find_intact_nodes(fbas: &Fbas, faulty: &NodeIdSet) -> NodeIdSet {
+    info!("Starting to look for intact nodes...");
+    let mut well_behaved = fbas.all_nodes();
+    well_behaved.difference_with(faulty);
+    let cancellation = Cancellation::new();
+    let mut monitor = SearchMonitor::new("intact nodes", &cancellation, None);
+    let intact_nodes = involved_nodes(&find_intact_sets(well_behaved, fbas, &mut monitor));
+    info!("Found {} intact nodes.", intact_nodes.len());
+    intact_nodes
+}
+/// Befouled nodes given that the `faulty` nodes are ill-behaved: all nodes that are not intact
+/// (see `find_intact_nodes`), including the faulty nodes themselves.
+pub fn find_befouled_nodes(fbas: &Fbas, faulty: &NodeIdSet) -> NodeIdSet {
+    let mut befouled_nodes = fbas.all_nodes();
+    befouled_nodes.difference_with(&find_intact_nodes(fbas, faulty));
+    befouled_nodes
+}
+/// Whether each two quorums intersect after deleting the `faulty` nodes, i.e., whether the faulty
+/// nodes can't cause a fork, no matter what they do.
+pub fn has_quorum_intersection_despite(fbas: &Fbas, faulty: &NodeIdSet) -> bool {
+    find_nonintersecting_quorums_via_sat_despite(fbas, faulty).is_none()
+}
+/// Whether all nodes except the `faulty` nodes form a quorum (or all nodes are faulty), i.e.,
+/// whether the faulty nodes can't block progress, no matter what they do. Note that, by this
+/// definition, nodes that can't be satisfied anyway need to be counted as faulty as well.
+pub fn has_quorum_availability_despite(fbas: &Fbas, faulty: &NodeIdSet) -> bool {
+    let mut well_behaved = fbas.all_nodes();
+    well_behaved.difference_with(faulty);
+    well_behaved.is_empty() || fbas.is_quorum(&well_behaved)
+}
+
+/// Minimal dispensable sets (DSets): sets of nodes `D` such that the FBAS enjoys quorum
+/// intersection despite `D` and the remaining nodes form a quorum (or `D` contains all nodes). In
+/// other words, no matter what the nodes in a DSet do, they can neither cause a fork nor block
+/// progress for the other nodes. Minimal DSets are the complements of maximal intact sets; if
+/// there are no intact sets at all, the only DSet is the set of all nodes.
+pub fn find_minimal_dsets(fbas: &Fbas) -> Vec<NodeIdSet> {
+    find_minimal_dsets_with(fbas, &SearchOptions::new())
+}
+/// Like `find_minimal_dsets`, but searches as configured via `options` (using only one thread).
+/// If the search is cancelled, the returned sets are DSets but might not be minimal.
+pub fn find_minimal_dsets_with(fbas: &Fbas, options: &SearchOptions) -> Vec<NodeIdSet> {
+    find_minimal_dsets_monitored(fbas, &mut options.monitor("minimal DSets"))
+}
+pub(crate) fn find_minimal_dsets_monitored(
+    fbas: &Fbas,
+    monitor: &mut SearchMonitor,
+) -> Vec<NodeIdSet> {
+    info!("Starting to look for minimal DSets...");
+    let all_nodes = fbas.all_nodes();
+    monitor.start_cluster(0, 1, all_nodes.len());
+    let intact_sets = find_intact_sets(all_nodes.clone(), fbas, monitor);
+    monitor.finish_cluster();
+    monitor.finish();
+    let mut minimal_dsets: Vec<NodeIdSet> = intact_sets
+        .into_iter()
+        .map(|intact_set| all_nodes.difference(&intact_set).collect())
+        .collect();
+    if minimal_dsets.is_empty() {
+        minimal_dsets.push(all_nodes);
+    }
+    let mut minimal_dsets = remove_non_minimal_node_sets(minimal_dsets);
+    minimal_dsets.sort_unstable();
+    minimal_dsets.sort_by_key(|x| x.len());
+    info!("Found {} minimal DSets.", minimal_dsets.len());
+    minimal_dsets
+}
+/// Whether `node_set` is a DSet (see `find_minimal_dsets`), i.e., whether the FBAS enjoys both
+/// quorum intersection and quorum availability despite `node_set`.
+pub fn is_dset(fbas: &Fbas, node_set: &NodeIdSet) -> bool {
+    has_quorum_availability_despite(fbas, node_set)
+        && has_quorum_intersection_despite(fbas, node_set)
+}
+
+/// Returns intact sets among `candidates` so that each intact set among the `candidates` is a
+/// subset of one of them.
+/// Stops early (returning only some of them) if `monitor` requests it.
+fn find_intact_sets(
+    candidates: NodeIdSet,
+    fbas: &Fbas,
+    monitor: &mut SearchMonitor,
+) -> Vec<NodeIdSet> {
+    let mut intact_sets = vec![];
+    intact_sets_finder_step(candidates, &mut intact_sets, fbas, monitor);
+    intact_sets
+}
+
+/// Adds all intact sets among the `candidates` to `intact_sets`. Each intact set is a quorum, so
+/// we look at the largest quorum among the candidates. If it isn't intact, there are two disjoint
+/// quorums in it (after deleting all other nodes). No intact set can intersect both of them, so we
+/// continue the search without the one and without the other.
+fn intact_sets_finder_step(
+    candidates: NodeIdSet,
+    intact_sets: &mut Vec<NodeIdSet>,
+    fbas: &Fbas,
+    monitor: &mut SearchMonitor,
+) {
+    if !monitor.visit() {
+        return;
+    }
+    let (quorum, _) = find_satisfiable_nodes(&candidates, fbas);
+    if quorum.is_empty()
+        || intact_sets
+            .iter()
+            .any(|intact_set| quorum.is_subset(intact_set))
+    {
+        return;
+    }
+    let mut others = fbas.all_nodes();
+    others.difference_with(&quorum);
+    if let Some(nonintersecting_quorums) =
+        find_nonintersecting_quorums_via_sat_despite(fbas, &others)
+    {
+        for nonintersecting_quorum in nonintersecting_quorums.iter() {
+            let mut remaining_candidates = quorum.clone();
+            remaining_candidates.difference_with(nonintersecting_quorum);
+            intact_sets_finder_step(remaining_candidates, intact_sets, fbas, monitor);
+        }
+    } else {
+        monitor.found(1);
+        intact_sets.push(quorum);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn all_nodes_intact_if_no_faulty_nodes_and_quorum_intersection() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct_trivial.json"));
+        assert_eq!(bitset![0, 1, 2], find_intact_nodes(&fbas, &bitset![]));
+        assert_eq!(bitset![], find_befouled_nodes(&fbas, &bitset![]));
+    }
+
+    #[test]
+    fn one_faulty_node_in_trivial_fbas() {
+        // each node requires 2 out of {n0, n1, n2}
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct_trivial.json"));
+        let faulty = bitset![2];
+
+        // after deleting n2, {n0} and {n1} are quorums
+        assert!(!has_quorum_intersection_despite(&fbas, &faulty));
+        assert!(has_quorum_availability_despite(&fbas, &faulty));
+        assert_eq!(bitset![], find_intact_nodes(&fbas, &faulty));
+        assert_eq!(bitset![0, 1, 2], find_befouled_nodes(&fbas, &faulty));
+    }
+
+    #[test]
+    fn two_faulty_nodes_in_trivial_fbas() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct_trivial.json"));
+        let faulty = bitset![1, 2];
+
+        assert_eq!(bitset![], find_intact_nodes(&fbas, &faulty));
+        assert_eq!(bitset![0, 1, 2], find_befouled_nodes(&fbas, &faulty));
+        assert!(has_quorum_intersection_despite(&fbas, &faulty));
+        assert!(!has_quorum_availability_despite(&fbas, &faulty));
+    }
+
+    #[test]
+    fn faulty_nodes_can_break_quorum_intersection() {
+        // each node requires 3 out of {n0, n1, n2, n3}
+        let fbas = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 3, "validators": ["n0", "n1", "n2", "n3"] }
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": { "threshold": 3, "validators": ["n0", "n1", "n2", "n3"] }
+            },
+            {
+                "publicKey": "n2",
+                "quorumSet": { "threshold": 3, "validators": ["n0", "n1", "n2", "n3"] }
+            },
+            {
+                "publicKey": "n3",
+                "quorumSet": { "threshold": 3, "validators": ["n0", "n1", "n2", "n3"] }
+            }
+        ]"#,
+        );
+        assert!(has_quorum_intersection_despite(&fbas, &bitset![0]));
+        assert_eq!(bitset![1, 2, 3], find_intact_nodes(&fbas, &bitset![0]));
+
+        // with two faulty nodes, {n2} and {n3} are quorums after deletion
+        assert!(!has_quorum_intersection_despite(&fbas, &bitset![0, 1]));
+        assert_eq!(bitset![], find_intact_nodes(&fbas, &bitset![0, 1]));
+        assert!(!has_quorum_availability_despite(&fbas, &bitset![0, 1]));
+    }
+
+    #[test]
+    fn nodes_in_separate_intact_sets_are_intact() {
+        // without quorum intersection, both {n0, n1} and {n2, n3} can still be intact
+        let fbas = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1"] }
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1"] }
+            },
+            {
+                "publicKey": "n2",
+                "quorumSet": { "threshold": 2, "validators": ["n2", "n3"] }
+            },
+            {
+                "publicKey": "n3",
+                "quorumSet": { "threshold": 2, "validators": ["n2", "n3"] }
+            },
+            {
+                "publicKey": "n4",
+                "quorumSet": { "threshold": 1, "validators": ["n0", "n2"] }
+            }
+        ]"#,
+        );
+        assert!(!has_quorum_intersection_despite(&fbas, &bitset![]));
+        assert_eq!(bitset![0, 1, 2, 3], find_intact_nodes(&fbas, &bitset![]));
+        assert_eq!(bitset![0, 1], find_intact_nodes(&fbas, &bitset![3]));
+        assert_eq!(
+            vec![bitset![0, 1, 4], bitset![2, 3, 4]],
+            find_minimal_dsets(&fbas)
+        );
+    }
+
+    #[test]
+    fn minimal_dsets_in_trivial_fbas() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct_trivial.json"));
+        assert_eq!(vec![bitset![]], find_minimal_dsets(&fbas));
+        assert!(is_dset(&fbas, &bitset![]));
+        assert!(!is_dset(&fbas, &bitset![2]));
+        assert!(is_dset(&fbas, &bitset![0, 1, 2]));
+    }
+
+    #[test]
+    fn minimal_dsets_are_dsets_and_complements_of_intact_nodes() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json"));
+        let minimal_dsets = find_minimal_dsets(&fbas);
+        assert_eq!(1, minimal_dsets.len());
+        assert!(is_dset(&fbas, &minimal_dsets[0]));
+        assert_eq!(minimal_dsets[0], find_befouled_nodes(&fbas, &bitset![]));
+    }
+
+    #[test]
+    fn all_nodes_form_only_minimal_dset_if_nothing_is_intact() {
+        let fbas = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1"] }
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": { "threshold": 3, "validators": ["n0", "n1"] }
+            }
+        ]"#,
+        );
+        assert_eq!(vec![bitset![0, 1]], find_minimal_dsets(&fbas));
+        assert!(!is_dset(&fbas, &bitset![0]));
+    }
+}
@@ -60,6 +60,46 @@ impl<'fbas> Groupings<'fbas> {
             .map(|q| self.merge_quorum_set(q))
             .collect()
     }
+    /// FBAS in which each grouping is one node, named after the grouping, and nodes that don't
+    /// belong to any grouping are kept as they are. All quorum sets are merged via
+    /// `merge_quorum_set`; each grouping uses the merged quorum set that is most common among its
+    /// members (the first member's, in case of a tie). Node IDs are assigned in the order of the
+    /// nodes (respectively, first grouping members) in the original FBAS.
+    /// Quorums that are disjoint in the original FBAS but contain nodes of the same grouping
+    /// intersect in the merged FBAS, so the merged FBAS can enjoy quorum intersection even if
+    /// the original FBAS doesn't.
+    /// Panics if the name of a grouping is also the public key of a node.
+    pub fn merged_fbas(&self) -> Fbas {
+        let mut merged_fbas = self.fbas.clone();
+        merged_fbas.map_quorum_sets(|_, quorum_set| self.merge_quorum_set(quorum_set));
+        for grouping in self.groupings.iter() {
+            if let Some(&first_member) = grouping.validators.first() {
+                let mut candidates: Vec<(&QuorumSet, usize)> = vec![];
+                for &member in grouping.validators.iter() {
+                    let quorum_set = &merged_fbas.nodes[member].quorum_set;
+                    if let Some(candidate) = candidates.iter_mut().find(|(q, _)| *q == quorum_set) {
+                        candidate.1 += 1;
+                    } else {
+                        candidates.push((quorum_set, 1));
+                    }
+                }
+                // `max_by_key` would pick the last of several most common quorum sets
+                let (quorum_set, _) = candidates
+                    .into_iter()
+                    .rev()
+                    .max_by_key(|&(_, count)| count)
+                    .unwrap();
+                let quorum_set = quorum_set.clone();
+                let representative = self.merge_node(first_member);
+                merged_fbas.swap_quorum_set(representative, quorum_set);
+                merged_fbas.rename_node(representative, grouping.name.clone());
+            }
+        }
+        let representatives: NodeIdSet = (0..merged_fbas.number_of_nodes())
+            .filter(|&node_id| self.merge_node(node_id) == node_id)
+            .collect();
+        merged_fbas.shrunken(representatives).0
+    }
 }
 
 #[cfg(test)]
@@ -99,6 +139,71 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn merged_fbas_has_one_node_per_organization() {
+        let fbas = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "a0",
+                "quorumSet": { "threshold": 2, "validators": ["a0", "b0", "c"] }
+            },
+            {
+                "publicKey": "a1",
+                "quorumSet": { "threshold": 2, "validators": ["a1", "b0", "c"] }
+            },
+            {
+                "publicKey": "a2",
+                "quorumSet": { "threshold": 1, "validators": ["c"] }
+            },
+            {
+                "publicKey": "b0",
+                "quorumSet": { "threshold": 2, "validators": ["a0", "a1", "b0"] }
+            },
+            {
+                "publicKey": "c",
+                "quorumSet": { "threshold": 1, "validators": ["b0"] }
+            }
+        ]"#,
+        );
+        let organizations = Groupings::organizations_from_json_str(
+            r#"[
+            { "id": "a", "name": "A", "validators": ["a0", "a1", "a2"] },
+            { "id": "b", "name": "B", "validators": ["b0"] }
+        ]"#,
+            &fbas,
+        );
+        let merged_fbas = organizations.merged_fbas();
+
+        assert_eq!(3, merged_fbas.number_of_nodes());
+        assert_eq!(
+            vec![Some(0), Some(1), Some(2), None],
+            ["A", "B", "c", "a0"]
+                .iter()
+                .map(|name| merged_fbas.get_node_id(name))
+                .collect::<Vec<Option<NodeId>>>()
+        );
+        // a2's quorum set is outvoted by those of a0 and a1
+        assert_eq!(
+            Some(QuorumSet {
+                threshold: 2,
+                validators: vec![0, 1, 2],
+                inner_quorum_sets: vec![],
+            }),
+            merged_fbas.get_quorum_set(0)
+        );
+        // A still counts twice here
+        assert_eq!(
+            Some(QuorumSet {
+                threshold: 2,
+                validators: vec![0, 0, 1],
+                inner_quorum_sets: vec![],
+            }),
+            merged_fbas.get_quorum_set(1)
+        );
+        assert!(merged_fbas.is_quorum(&bitset![0, 1, 2]));
+        assert!(!merged_fbas.is_quorum(&bitset![1, 2]));
+    }
+
     #[test]
     fn merge_node_sets_by_isp() {
         let fbas_input = r#"[
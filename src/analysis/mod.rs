@@ -1,29 +1,58 @@
 use super::*;
 
+mod cancellation;
 mod front_end;
+mod progress;
 mod results;
 
 mod blocking_sets;
+mod cascade;
+mod intactness;
 mod quorums;
+mod sat;
 mod splitting_sets;
 mod symmetric_clusters;
 
 mod merge_by_group;
+mod parallel;
 pub mod preprocessing;
 pub mod sets;
 pub mod timing;
 
-pub use front_end::Analysis;
+pub use cancellation::Cancellation;
+pub use front_end::{Analysis, AnalysisCache, QuorumIntersectionBackend};
+pub use progress::{ProgressListener, SearchOptions, SearchProgress};
 pub use results::{NodeIdSetResult, NodeIdSetVecResult};
 
-pub use blocking_sets::find_minimal_blocking_sets;
-pub use quorums::{find_minimal_quorums, find_nonintersecting_quorums};
-pub use splitting_sets::find_minimal_splitting_sets;
+pub use blocking_sets::{
+    find_minimal_blocking_sets, find_minimal_blocking_sets_for, find_minimal_blocking_sets_with,
+    find_smallest_blocking_set, find_smallest_blocking_set_with,
+};
+pub use cascade::{find_surviving_minimal_quorums, simulate_cascade, Cascade};
+pub use intactness::{
+    find_befouled_nodes, find_intact_nodes, find_minimal_dsets, find_minimal_dsets_with,
+    has_quorum_availability_despite, has_quorum_intersection_despite, is_dset,
+};
+pub use quorums::{
+    find_minimal_quorums, find_minimal_quorums_containing, find_minimal_quorums_with,
+    find_nonintersecting_quorums, find_nonintersecting_quorums_with,
+};
+pub use sat::find_nonintersecting_quorums_via_sat;
+pub use splitting_sets::{
+    find_minimal_splitting_sets, find_minimal_splitting_sets_with, find_smallest_splitting_set,
+    find_smallest_splitting_set_with,
+};
 pub use symmetric_clusters::{find_symmetric_clusters, find_symmetric_top_tier};
 
+pub(crate) use blocking_sets::*;
+pub(crate) use intactness::find_minimal_dsets_monitored;
+pub(crate) use parallel::search_cluster;
 pub(crate) use preprocessing::*;
+pub(crate) use progress::{SearchMonitor, SharedProgressListener};
 pub(crate) use quorums::*;
+pub(crate) use sat::find_nonintersecting_quorums_via_sat_despite;
 pub(crate) use sets::*;
+pub(crate) use splitting_sets::*;
 pub(crate) use symmetric_clusters::*;
 
 #[cfg(test)]
@@ -124,6 +153,526 @@ mod tests {
         );
     }
 
+    #[test]
+    fn smallest_sets_nontrivial() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json"));
+        let analysis = Analysis::new(&fbas);
+
+        assert_eq!(
+            Some(2),
+            analysis.smallest_blocking_set().map(|result| result.len())
+        );
+        assert_eq!(
+            Some(1),
+            analysis.smallest_splitting_set().map(|result| result.len())
+        );
+        assert_eq!(analysis.minimal_blocking_sets().min(), 2);
+        assert_eq!(analysis.minimal_splitting_sets().min(), 1);
+    }
+
+    #[test]
+    fn smallest_splitting_set_on_broken() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/broken.json"));
+        let analysis = Analysis::new(&fbas);
+
+        let actual = analysis.smallest_splitting_set().unwrap().unwrap();
+
+        assert_eq!(bitset![], actual);
+    }
+
+    #[test]
+    #[ignore]
+    fn smallest_sets_big() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/stellarbeat_nodes_2019-09-17.json"));
+        let analysis = Analysis::new(&fbas);
+
+        let smallest_blocking_set = analysis.smallest_blocking_set().unwrap();
+        let smallest_splitting_set = analysis.smallest_splitting_set().unwrap();
+        assert_eq!(
+            analysis.minimal_blocking_sets().min(),
+            smallest_blocking_set.len()
+        );
+        assert_eq!(
+            analysis.minimal_splitting_sets().min(),
+            smallest_splitting_set.len()
+        );
+    }
+
+    #[test]
+    fn cancelled_analysis_yields_partial_results() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/broken.json"));
+        let cancellation = Cancellation::new().with_deadline(std::time::Instant::now());
+        let analysis = Analysis::new(&fbas).with_cancellation(cancellation);
+
+        assert!(analysis.is_cancelled());
+        let minimal_quorums = analysis.minimal_quorums();
+        assert!(minimal_quorums.is_partial());
+        assert!(minimal_quorums.len() < find_minimal_quorums(&fbas).len());
+        assert!(analysis.minimal_blocking_sets().is_partial());
+        assert!(analysis.top_tier().is_partial());
+        // partial results are cached, too
+        assert_eq!(minimal_quorums, analysis.minimal_quorums());
+    }
+
+    #[test]
+    fn analysis_with_unrequested_cancellation_yields_complete_results() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json"));
+        let token = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let analysis =
+            Analysis::new(&fbas).with_cancellation(Cancellation::new().with_token(token));
+
+        assert!(!analysis.is_cancelled());
+        assert!(!analysis.minimal_quorums().is_partial());
+        assert!(!analysis.minimal_splitting_sets().is_partial());
+        assert!(!analysis.top_tier().is_partial());
+        assert_eq!(analysis.minimal_splitting_sets().len(), 3);
+    }
+
+    #[test]
+    fn analysis_reports_progress_of_searches() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json"));
+        let reports = std::sync::Arc::new(std::sync::Mutex::new(vec![]));
+        let reports_clone = reports.clone();
+        let analysis = Analysis::new(&fbas).with_progress_listener(move |p: &SearchProgress| {
+            reports_clone.lock().unwrap().push(*p)
+        });
+
+        let number_of_minimal_quorums = analysis.minimal_quorums().len();
+        let reports = reports.lock().unwrap();
+        let last_report = reports.last().unwrap();
+        assert_eq!("minimal quorums", last_report.search);
+        assert!(last_report.finished);
+        assert_eq!(1., last_report.estimated_completion);
+        assert_eq!(number_of_minimal_quorums, last_report.sets_found);
+        assert!(reports
+            .windows(2)
+            .all(|w| w[0].estimated_completion <= w[1].estimated_completion));
+    }
+
+    #[test]
+    fn minimal_quorums_with_progress_reports_visited_nodes() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/broken.json"));
+        let last_report = std::cell::Cell::new(None);
+        let listener = |p: &SearchProgress| last_report.set(Some(*p));
+        let minimal_quorums = find_minimal_quorums_with(
+            &fbas,
+            &SearchOptions::new().with_progress_listener(&listener),
+        );
+        let last_report = last_report.get().unwrap();
+        assert!(last_report.finished);
+        assert!(last_report.nodes_visited > 0);
+        assert_eq!(1., last_report.estimated_completion);
+        assert_eq!(minimal_quorums.len(), last_report.sets_found);
+    }
+
+    /// Asymmetric FBAS in which each node trusts itself and its `k - 1` successors on a ring.
+    fn ring_fbas(n: usize, k: usize, threshold: usize) -> Fbas {
+        let nodes: Vec<String> = (0..n)
+            .map(|i| {
+                let validators: Vec<String> =
+                    (i..i + k).map(|j| format!("\"n{}\"", j % n)).collect();
+                format!(
+                    r#"{{ "publicKey": "n{}", "quorumSet": {{ "threshold": {}, "validators": [{}] }} }}"#,
+                    i,
+                    threshold,
+                    validators.join(", ")
+                )
+            })
+            .collect();
+        Fbas::from_json_str(&format!("[{}]", nodes.join(", ")))
+    }
+
+    #[test]
+    fn parallel_searches_yield_same_results_as_sequential_ones() {
+        for fbas in [
+            ring_fbas(12, 5, 4),
+            ring_fbas(10, 4, 2),
+            Fbas::from_json_file(Path::new("test_data/correct.json")),
+            Fbas::from_json_file(Path::new("test_data/broken.json")),
+        ] {
+            let minimal_quorums = find_minimal_quorums(&fbas);
+            assert!(!minimal_quorums.is_empty());
+            let options = |n| SearchOptions::new().with_number_of_threads(n);
+            assert_eq!(
+                minimal_quorums,
+                find_minimal_quorums_with(&fbas, &options(4))
+            );
+            assert_eq!(
+                find_minimal_blocking_sets(&fbas),
+                find_minimal_blocking_sets_with(&fbas, &options(3))
+            );
+            assert_eq!(
+                find_minimal_splitting_sets(&fbas, &minimal_quorums),
+                find_minimal_splitting_sets_with(&fbas, &minimal_quorums, &options(2))
+            );
+        }
+    }
+
+    #[test]
+    fn multi_threaded_analysis_yields_same_results() {
+        let fbas = ring_fbas(12, 5, 4);
+        let analysis = Analysis::new(&fbas);
+        let multi_threaded_analysis = Analysis::new(&fbas).with_number_of_threads(4);
+
+        assert_eq!(
+            analysis.minimal_quorums(),
+            multi_threaded_analysis.minimal_quorums()
+        );
+        assert_eq!(
+            analysis.minimal_blocking_sets(),
+            multi_threaded_analysis.minimal_blocking_sets()
+        );
+        assert_eq!(
+            analysis.minimal_splitting_sets(),
+            multi_threaded_analysis.minimal_splitting_sets()
+        );
+    }
+
+    #[test]
+    fn analysis_is_send_and_sync() {
+        fn assert_send_and_sync<T: Send + Sync>() {}
+        assert_send_and_sync::<Analysis>();
+    }
+
+    #[test]
+    fn shared_analysis_yields_same_results() {
+        for fbas in [
+            Fbas::from_json_file(Path::new("test_data/correct.json")),
+            Fbas::from_json_file(Path::new("test_data/broken.json")),
+        ] {
+            let analysis = Analysis::new(&fbas);
+            let expected = (
+                analysis.minimal_blocking_sets(),
+                analysis.minimal_splitting_sets(),
+                analysis.top_tier(),
+            );
+            let shared_analysis = Analysis::new(&fbas);
+            std::thread::scope(|scope| {
+                let handles: Vec<_> = (0..4)
+                    .map(|_| {
+                        scope.spawn(|| {
+                            (
+                                shared_analysis.minimal_blocking_sets(),
+                                shared_analysis.minimal_splitting_sets(),
+                                shared_analysis.top_tier(),
+                            )
+                        })
+                    })
+                    .collect();
+                for handle in handles {
+                    assert_eq!(expected, handle.join().unwrap());
+                }
+            });
+        }
+    }
+
+    #[test]
+    fn concurrent_requests_compute_results_only_once() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json"));
+        let finished_searches = std::sync::Arc::new(std::sync::Mutex::new(vec![]));
+        let finished_searches_clone = finished_searches.clone();
+        let analysis = Analysis::new(&fbas).with_progress_listener(move |p: &SearchProgress| {
+            if p.finished {
+                finished_searches_clone.lock().unwrap().push(p.search)
+            }
+        });
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    analysis.minimal_splitting_sets();
+                    analysis.minimal_blocking_sets();
+                    analysis.has_quorum_intersection();
+                });
+            }
+        });
+        let mut finished_searches = finished_searches.lock().unwrap().clone();
+        finished_searches.sort_unstable();
+        assert_eq!(
+            vec![
+                "minimal blocking sets",
+                "minimal quorums",
+                "minimal splitting sets"
+            ],
+            finished_searches
+        );
+    }
+
+    /// FBAS in which `n1` can never be satisfied, so that there are no quorums at all.
+    fn no_quorum_fbas() -> Fbas {
+        Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1"] }
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": { "threshold": 3, "validators": ["n0", "n1"] }
+            }
+        ]"#,
+        )
+    }
+
+    #[test]
+    fn sat_backend_agrees_with_minimal_quorums() {
+        for fbas in [
+            ring_fbas(12, 5, 4),
+            ring_fbas(10, 4, 2),
+            Fbas::from_json_file(Path::new("test_data/correct_trivial.json")),
+            Fbas::from_json_file(Path::new("test_data/broken_trivial.json")),
+            Fbas::from_json_file(Path::new("test_data/correct.json")),
+            Fbas::from_json_file(Path::new("test_data/broken.json")),
+            no_quorum_fbas(),
+        ] {
+            let expected_analysis = Analysis::new(&fbas);
+            let expected = expected_analysis.has_quorum_intersection();
+            let analysis = Analysis::new(&fbas)
+                .with_quorum_intersection_backend(QuorumIntersectionBackend::Sat);
+            assert_eq!(expected, analysis.has_quorum_intersection());
+            assert_eq!(
+                expected_analysis.minimal_splitting_sets(),
+                analysis.minimal_splitting_sets()
+            );
+
+            let (actual, quorums) = analysis.has_quorum_intersection_via_sat();
+            assert_eq!(expected, actual);
+            if let Some(quorums) = quorums {
+                let quorums = quorums.unwrap();
+                assert!(!expected);
+                assert!(quorums.iter().all(|quorum| fbas.is_quorum(quorum)));
+                assert!(quorums.is_empty() || quorums[0].is_disjoint(&quorums[1]));
+            } else {
+                assert!(expected);
+            }
+        }
+    }
+
+    #[test]
+    fn sat_backend_ignores_cancellation() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json"));
+        let analysis = Analysis::new(&fbas)
+            .with_cancellation(Cancellation::new().with_deadline(std::time::Instant::now()))
+            .with_quorum_intersection_backend(QuorumIntersectionBackend::Sat);
+
+        assert!(analysis.is_cancelled());
+        assert!(analysis.has_quorum_intersection());
+    }
+
+    #[test]
+    fn analysis_partitions_nodes_into_intact_and_befouled_nodes() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json"));
+        let analysis = Analysis::new(&fbas);
+
+        let intact_nodes = analysis.intact_nodes(&bitset![]).unwrap();
+        let befouled_nodes = analysis.befouled_nodes(&bitset![]).unwrap();
+        assert!(intact_nodes.contains(0));
+        assert!(intact_nodes.is_disjoint(&befouled_nodes));
+        assert_eq!(
+            fbas.all_nodes(),
+            intact_nodes.union(&befouled_nodes).collect()
+        );
+        assert!(analysis.has_quorum_intersection_despite(&bitset![]));
+
+        // 2 out of 3 top tier nodes are needed for a quorum
+        let faulty = bitset![0];
+        assert!(analysis.befouled_nodes(&faulty).unwrap().contains(0));
+        assert!(!analysis.has_quorum_intersection_despite(&faulty));
+        assert!(!analysis.has_quorum_availability_despite(&faulty));
+    }
+
+    #[test]
+    fn analysis_simulates_cascading_failures() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json"));
+        let analysis = Analysis::new(&fbas);
+
+        // 2 out of 3 top tier nodes are needed for a quorum
+        let failing = bitset![0];
+        assert_eq!(failing, analysis.cascade_rounds(&failing).unwrap()[0]);
+        assert!(!analysis.cascade_halts_network(&failing));
+        assert_eq!(
+            vec![bitset![1, 10]],
+            analysis.surviving_minimal_quorums(&failing).unwrap()
+        );
+        assert!(analysis
+            .surviving_nodes(&failing)
+            .unwrap()
+            .is_superset(&bitset![1, 10]));
+
+        let failing = bitset![0, 1];
+        assert!(analysis.cascade_halts_network(&failing));
+        assert!(analysis.surviving_nodes(&failing).is_empty());
+        assert!(analysis.surviving_minimal_quorums(&failing).is_empty());
+    }
+
+    #[test]
+    fn analysis_minimal_dsets_are_befouled_nodes() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json"));
+        let analysis = Analysis::new(&fbas);
+
+        let minimal_dsets = analysis.minimal_dsets().unwrap();
+        assert_eq!(
+            vec![analysis.befouled_nodes(&bitset![]).unwrap()],
+            minimal_dsets
+        );
+        assert!(analysis.is_dset(&minimal_dsets[0]));
+        assert!(analysis.is_dset(&fbas.all_nodes()));
+        assert!(!analysis.is_dset(&bitset![0]));
+    }
+
+    #[test]
+    fn cancelled_minimal_dsets_are_partial() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json"));
+        let cancellation = Cancellation::new().with_deadline(std::time::Instant::now());
+        let analysis = Analysis::new(&fbas).with_cancellation(cancellation);
+
+        let minimal_dsets = analysis.minimal_dsets();
+        assert!(minimal_dsets.is_partial());
+        assert_eq!(vec![fbas.all_nodes()], minimal_dsets.unwrap());
+        assert!(!Analysis::new(&fbas).minimal_dsets().is_partial());
+    }
+
+    #[test]
+    fn analysis_finds_sets_for_node_with_original_ids() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json"));
+        let analysis = Analysis::new(&fbas);
+
+        assert_eq!(
+            vec![bitset![0, 1], bitset![0, 10]],
+            analysis.minimal_quorums_containing(0).unwrap()
+        );
+        assert_eq!(
+            vec![bitset![1, 10]],
+            analysis.minimal_blocking_sets_for(0).unwrap()
+        );
+    }
+
+    fn temp_cache_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!(
+            "fbas_analyzer_test_{}_{}.json",
+            name,
+            std::process::id()
+        ))
+    }
+
+    #[test]
+    fn analysis_with_cache_reuses_saved_results() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json"));
+        let cache_path = temp_cache_path("reuse");
+        let analysis = Analysis::with_cache(&fbas, &cache_path).unwrap();
+        let minimal_quorums = analysis.minimal_quorums();
+        let minimal_splitting_sets = analysis.minimal_splitting_sets();
+        analysis.save_cache(&cache_path).unwrap();
+
+        // cancelled right away, so that nothing can be computed anew
+        let cancellation = Cancellation::new().with_deadline(std::time::Instant::now());
+        let restored_analysis = Analysis::with_cache(&fbas, &cache_path)
+            .unwrap()
+            .with_cancellation(cancellation);
+        std::fs::remove_file(&cache_path).unwrap();
+
+        assert!(restored_analysis.has_quorum_intersection());
+        assert_eq!(minimal_quorums, restored_analysis.minimal_quorums());
+        assert_eq!(
+            minimal_splitting_sets,
+            restored_analysis.minimal_splitting_sets()
+        );
+        assert!(!restored_analysis.minimal_splitting_sets().is_partial());
+        assert!(!restored_analysis.top_tier().is_partial());
+        assert!(restored_analysis.minimal_blocking_sets().is_partial());
+    }
+
+    #[test]
+    fn analysis_with_cache_translates_node_ids() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json"));
+        let reordered_fbas = fbas.to_standard_form();
+        let cache_path = temp_cache_path("translate");
+        let analysis = Analysis::new(&fbas);
+        analysis.minimal_blocking_sets();
+        analysis.save_cache(&cache_path).unwrap();
+
+        let restored_analysis = Analysis::with_cache(&reordered_fbas, &cache_path).unwrap();
+        std::fs::remove_file(&cache_path).unwrap();
+
+        assert_eq!(
+            Analysis::new(&reordered_fbas).minimal_blocking_sets(),
+            restored_analysis.minimal_blocking_sets()
+        );
+    }
+
+    #[test]
+    fn analysis_with_cache_ignores_partial_results_and_other_fbass() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json"));
+        let other_fbas = Fbas::from_json_file(Path::new("test_data/broken.json"));
+        let cache_path = temp_cache_path("ignore");
+        let analysis = Analysis::new(&other_fbas);
+        analysis.minimal_quorums();
+        analysis.save_cache(&cache_path).unwrap();
+
+        let restored_analysis = Analysis::with_cache(&fbas, &cache_path).unwrap();
+        assert_eq!(
+            Analysis::new(&fbas).minimal_quorums(),
+            restored_analysis.minimal_quorums()
+        );
+
+        let cancellation = Cancellation::new().with_deadline(std::time::Instant::now());
+        let cancelled_analysis = Analysis::new(&fbas).with_cancellation(cancellation);
+        cancelled_analysis.minimal_quorums();
+        cancelled_analysis.save_cache(&cache_path).unwrap();
+
+        let restored_analysis = Analysis::with_cache(&fbas, &cache_path).unwrap();
+        std::fs::remove_file(&cache_path).unwrap();
+        assert!(!restored_analysis.minimal_quorums().is_partial());
+    }
+
+    #[test]
+    fn analysis_with_cache_rejects_public_keys_of_irrelevant_nodes() {
+        let fbas = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1"] }
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1"] }
+            },
+            {
+                "publicKey": "n2",
+                "quorumSet": { "threshold": 1, "validators": ["n0"] }
+            }
+        ]"#,
+        );
+        let cache_path = temp_cache_path("irrelevant");
+        let analysis = Analysis::new(&fbas);
+        analysis.minimal_quorums();
+        analysis.save_cache(&cache_path).unwrap();
+        let mut cache: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&cache_path).unwrap()).unwrap();
+        cache["shrunkenPublicKeys"] = serde_json::json!(["n0", "n2"]);
+        std::fs::write(&cache_path, cache.to_string()).unwrap();
+
+        let result = Analysis::with_cache(&fbas, &cache_path);
+        std::fs::remove_file(&cache_path).unwrap();
+        assert!(matches!(result, Err(Error::InvalidAnalysisCache(_))));
+    }
+
+    #[test]
+    fn analysis_with_cached_results_reuses_results_in_memory() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json"));
+        let analysis = Analysis::new(&fbas);
+        let expected = analysis.minimal_blocking_sets();
+        let cached_results = analysis.cached_results();
+        assert_eq!(fbas.standard_form_hash(), cached_results.fbas_hash());
+
+        // would yield partial results if it had to search again
+        let cancellation = Cancellation::new().with_deadline(std::time::Instant::now());
+        let restored_analysis = Analysis::with_cached_results(&fbas, &cached_results)
+            .unwrap()
+            .with_cancellation(cancellation);
+        assert_eq!(expected, restored_analysis.minimal_blocking_sets());
+        assert!(restored_analysis.minimal_quorums().is_partial());
+    }
+
     #[test]
     fn splitting_sets_on_broken() {
         let fbas = Fbas::from_json_file(Path::new("test_data/broken.json"));
@@ -197,6 +746,216 @@ mod tests {
         );
     }
 
+    #[test]
+    fn grouped_analysis_matches_merging_afterwards() {
+        // four organizations that all require three of the four organizations, plus a node
+        // without organization that nobody depends on
+        let org_sizes = [("A", 3), ("B", 3), ("C", 3), ("D", 3)];
+        let members = |org: &str, size: usize| -> Vec<String> {
+            (0..size).map(|i| format!("{}{}", org, i)).collect()
+        };
+        let inner_quorum_sets: Vec<String> = org_sizes
+            .iter()
+            .map(|&(org, size)| {
+                format!(
+                    r#"{{ "threshold": {}, "validators": {:?} }}"#,
+                    size / 2 + 1,
+                    members(org, size)
+                )
+            })
+            .collect();
+        let quorum_set = format!(
+            r#"{{ "threshold": 3, "validators": [], "innerQuorumSets": [{}] }}"#,
+            inner_quorum_sets.join(", ")
+        );
+        let nodes: Vec<String> = org_sizes
+            .iter()
+            .flat_map(|&(org, size)| members(org, size))
+            .chain(std::iter::once("X".to_string()))
+            .map(|node| {
+                format!(
+                    r#"{{ "publicKey": "{}", "quorumSet": {} }}"#,
+                    node, quorum_set
+                )
+            })
+            .collect();
+        let fbas = Fbas::from_json_str(&format!("[{}]", nodes.join(", ")));
+        let organizations_json: Vec<String> = org_sizes
+            .iter()
+            .map(|&(org, size)| {
+                format!(
+                    r#"{{ "id": "{}", "name": "Org {}", "validators": {:?} }}"#,
+                    org,
+                    org,
+                    members(org, size)
+                )
+            })
+            .collect();
+        let organizations = Groupings::organizations_from_json_str(
+            &format!("[{}]", organizations_json.join(", ")),
+            &fbas,
+        );
+        let analysis = Analysis::new(&fbas);
+        let grouped_analysis = Analysis::new_grouped(&fbas, &organizations);
+        let merged_fbas = grouped_analysis.fbas();
+
+        let merged_afterwards = |sets: NodeIdSetVecResult| -> BTreeSet<BTreeSet<String>> {
+            sets.merged_by_group(&organizations)
+                .minimal_sets()
+                .into_pretty_vec_vec(&fbas, Some(&organizations))
+                .into_iter()
+                .map(|set| set.into_iter().collect())
+                .collect()
+        };
+        let merged_up_front = |sets: NodeIdSetVecResult| -> BTreeSet<BTreeSet<String>> {
+            sets.into_pretty_vec_vec(merged_fbas, None)
+                .into_iter()
+                .map(|set| set.into_iter().collect())
+                .collect()
+        };
+        assert!(merged_fbas.number_of_nodes() < fbas.number_of_nodes());
+        assert!(grouped_analysis.has_quorum_intersection());
+        assert_eq!(
+            merged_afterwards(analysis.minimal_quorums()),
+            merged_up_front(grouped_analysis.minimal_quorums())
+        );
+        assert_eq!(
+            merged_afterwards(analysis.minimal_blocking_sets()),
+            merged_up_front(grouped_analysis.minimal_blocking_sets())
+        );
+        assert_eq!(
+            merged_afterwards(analysis.minimal_splitting_sets()),
+            merged_up_front(grouped_analysis.minimal_splitting_sets())
+        );
+        let mut expected_top_tier = analysis
+            .top_tier()
+            .merged_by_group(&organizations)
+            .into_pretty_vec(&fbas, Some(&organizations));
+        let mut actual_top_tier = grouped_analysis
+            .top_tier()
+            .into_pretty_vec(merged_fbas, None);
+        expected_top_tier.sort();
+        actual_top_tier.sort();
+        assert_eq!(expected_top_tier, actual_top_tier);
+        assert_eq!(vec!["Org A", "Org B", "Org C", "Org D"], actual_top_tier);
+    }
+
+    #[test]
+    #[ignore]
+    fn grouped_analysis_matches_merging_afterwards_big() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/stellarbeat_nodes_2019-09-17.json"));
+        let organizations = Groupings::organizations_from_json_file(
+            Path::new("test_data/stellarbeat_organizations_2019-09-17.json"),
+            &fbas,
+        );
+        let analysis = Analysis::new(&fbas);
+        let grouped_analysis = Analysis::new_grouped(&fbas, &organizations);
+        let merged_fbas = grouped_analysis.fbas();
+
+        let merged_afterwards = |sets: NodeIdSetVecResult| -> BTreeSet<BTreeSet<String>> {
+            sets.merged_by_group(&organizations)
+                .minimal_sets()
+                .into_pretty_vec_vec(&fbas, Some(&organizations))
+                .into_iter()
+                .map(|set| set.into_iter().collect())
+                .collect()
+        };
+        let merged_up_front = |sets: NodeIdSetVecResult| -> BTreeSet<BTreeSet<String>> {
+            sets.into_pretty_vec_vec(merged_fbas, None)
+                .into_iter()
+                .map(|set| set.into_iter().collect())
+                .collect()
+        };
+        assert!(merged_fbas.number_of_nodes() < fbas.number_of_nodes());
+        assert!(grouped_analysis.has_quorum_intersection());
+        assert_eq!(
+            merged_afterwards(analysis.minimal_quorums()),
+            merged_up_front(grouped_analysis.minimal_quorums())
+        );
+        assert_eq!(
+            merged_afterwards(analysis.minimal_blocking_sets()),
+            merged_up_front(grouped_analysis.minimal_blocking_sets())
+        );
+        assert_eq!(
+            merged_afterwards(analysis.minimal_splitting_sets()),
+            merged_up_front(grouped_analysis.minimal_splitting_sets())
+        );
+        let mut expected_top_tier = analysis
+            .top_tier()
+            .merged_by_group(&organizations)
+            .into_pretty_vec(&fbas, Some(&organizations));
+        let mut actual_top_tier = grouped_analysis
+            .top_tier()
+            .into_pretty_vec(merged_fbas, None);
+        expected_top_tier.sort();
+        actual_top_tier.sort();
+        assert_eq!(expected_top_tier, actual_top_tier);
+        assert!(actual_top_tier.contains(&"Stellar Development Foundation".to_string()));
+    }
+
+    #[test]
+    fn grouped_analysis_can_hide_missing_quorum_intersection() {
+        // every node needs two of three organizations; {A0, B0} and {A1, B1} are disjoint quorums
+        let quorum_set = r#"{
+            "threshold": 2,
+            "validators": [],
+            "innerQuorumSets": [
+                { "threshold": 1, "validators": ["A0", "A1"] },
+                { "threshold": 1, "validators": ["B0", "B1"] },
+                { "threshold": 1, "validators": ["C0", "C1"] }
+            ]
+        }"#;
+        let nodes: Vec<String> = ["A0", "A1", "B0", "B1", "C0", "C1"]
+            .iter()
+            .map(|node| {
+                format!(
+                    r#"{{ "publicKey": "{}", "quorumSet": {} }}"#,
+                    node, quorum_set
+                )
+            })
+            .collect();
+        let fbas = Fbas::from_json_str(&format!("[{}]", nodes.join(", ")));
+        let organizations = Groupings::organizations_from_json_str(
+            r#"[
+            { "id": "A", "name": "A", "validators": ["A0", "A1"] },
+            { "id": "B", "name": "B", "validators": ["B0", "B1"] },
+            { "id": "C", "name": "C", "validators": ["C0", "C1"] }
+            ]"#,
+            &fbas,
+        );
+        let analysis = Analysis::new(&fbas);
+        let grouped_analysis = Analysis::new_grouped(&fbas, &organizations);
+
+        assert!(!analysis.has_quorum_intersection());
+        assert_eq!(
+            vec![bitset![]],
+            analysis
+                .minimal_splitting_sets()
+                .merged_by_group(&organizations)
+                .minimal_sets()
+                .unwrap()
+        );
+        // grouped, {A, B} and {A, C} intersect, so the missing quorum intersection goes unnoticed
+        assert!(grouped_analysis.has_quorum_intersection());
+        assert_eq!(
+            vec![vec!["A"], vec!["B"], vec!["C"]],
+            grouped_analysis
+                .minimal_splitting_sets()
+                .into_pretty_vec_vec(grouped_analysis.fbas(), None)
+        );
+        // minimal quorums still match
+        assert_eq!(
+            analysis
+                .minimal_quorums()
+                .merged_by_group(&organizations)
+                .minimal_sets()
+                .into_pretty_vec_vec(&fbas, Some(&organizations)),
+            grouped_analysis
+                .minimal_quorums()
+                .into_pretty_vec_vec(grouped_analysis.fbas(), None)
+        );
+    }
+
     #[test]
     #[ignore]
     fn top_tier_analysis_big() {
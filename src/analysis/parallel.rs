@@ -0,0 +1,89 @@
+use super::*;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+/// We never split off more than this many decisions (i.e., there are at most `2^MAX_SPLIT_DEPTH`
+/// work items per cluster).
+const MAX_SPLIT_DEPTH: usize = 16;
+
+/// How many work items per thread we aim for; more work items mean better load balancing but
+/// also more search tree nodes that are visited redundantly.
+const WORK_ITEMS_PER_THREAD: usize = 8;
+
+/// Search the current cluster of `monitor` using `search`, which must build its search tree from
+/// scratch, using the monitor it gets passed.
+///
+/// If `monitor` allows for more than one thread, the search tree is split at the first few
+/// candidate decisions into work items that are processed by a pool of threads. As search tree
+/// nodes above the split are visited by several work items, the same sets can be found multiple
+/// times; the merged results are therefore reduced to minimal node sets.
+pub(crate) fn search_cluster<F>(monitor: &mut SearchMonitor, search: F) -> Vec<NodeIdSet>
+where
+    F: Fn(&mut SearchMonitor) -> Vec<NodeIdSet> + Sync,
+{
+    let number_of_threads = monitor.number_of_threads();
+    let split_depth = split_depth(number_of_threads, monitor.cluster_size());
+    if split_depth == 0 {
+        return search(monitor);
+    }
+    let number_of_work_items = 1 << split_depth;
+    debug!(
+        "Splitting search into {} work items for {} threads...",
+        number_of_work_items, number_of_threads
+    );
+    let work_item_monitors = monitor.work_item_monitors();
+    let next_work_item = AtomicUsize::new(0);
+    let found_sets = Mutex::new(vec![]);
+    let nodes_visited = Mutex::new(0);
+    thread::scope(|scope| {
+        for _ in 0..number_of_threads {
+            scope.spawn(|| loop {
+                let work_item = next_work_item.fetch_add(1, Ordering::Relaxed);
+                if work_item >= number_of_work_items {
+                    break;
+                }
+                let mut work_item_monitor = work_item_monitors(work_item, split_depth);
+                let mut found_sets_in_work_item = search(&mut work_item_monitor);
+                found_sets
+                    .lock()
+                    .unwrap()
+                    .append(&mut found_sets_in_work_item);
+                *nodes_visited.lock().unwrap() += work_item_monitor.nodes_visited();
+            });
+        }
+    });
+    monitor.add_visited(nodes_visited.into_inner().unwrap());
+    let found_sets = found_sets.into_inner().unwrap();
+    debug!("Found {} sets in work items. Merging...", found_sets.len());
+    let found_sets = remove_non_minimal_node_sets(found_sets);
+    monitor.found(found_sets.len());
+    found_sets
+}
+
+/// Number of decisions to split off so that there are enough work items for all threads.
+/// Zero if there is just one thread.
+fn split_depth(number_of_threads: usize, cluster_size: usize) -> usize {
+    if number_of_threads <= 1 {
+        0
+    } else {
+        let number_of_work_items = number_of_threads * WORK_ITEMS_PER_THREAD;
+        let split_depth = number_of_work_items.next_power_of_two().trailing_zeros() as usize;
+        split_depth.min(MAX_SPLIT_DEPTH).min(cluster_size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_depth_yields_enough_work_items() {
+        assert_eq!(0, split_depth(1, 100));
+        assert_eq!(4, split_depth(2, 100));
+        assert_eq!(5, split_depth(3, 100));
+        assert_eq!(3, split_depth(4, 3));
+        assert_eq!(MAX_SPLIT_DEPTH, split_depth(1_000_000, 100));
+    }
+}
@@ -32,14 +32,37 @@ impl Fbas {
         relevant_nodes
     }
     /// Reduces the FBAS to nodes relevant to analysis (nodes part of a quorum-containing strongly
-    /// connected component) and reorders node IDs so that nodes are sorted by public key.
+    /// connected component), reorders node IDs so that nodes are sorted by public key and
+    /// normalizes all quorum sets (see `QuorumSet::normalized`), so that cosmetic differences in
+    /// quorum set configurations don't lead to different standard forms.
     pub fn to_standard_form(&self) -> Self {
         let shrunken_self = self.shrunken(self.relevant_nodes()).0;
         let mut raw_shrunken_self = shrunken_self.to_raw();
         raw_shrunken_self
             .0
             .sort_by_cached_key(|n| n.public_key.clone());
-        Fbas::from_raw(raw_shrunken_self)
+        let mut standard_form = Fbas::from_raw(raw_shrunken_self);
+        for node in standard_form.nodes.iter_mut() {
+            node.quorum_set = node.quorum_set.normalized();
+        }
+        standard_form
+    }
+    /// Makes all nodes except `node` additionally require `node` in each of their quorum slices,
+    /// so that the quorums of the resulting FBAS are exactly the quorums containing `node`. The
+    /// result is reduced to relevant nodes (see `relevant_nodes`).
+    pub(crate) fn anchored_at(&self, node: NodeId) -> (Self, ShrinkManager) {
+        let mut anchored_self = self.clone();
+        for (node_id, other_node) in anchored_self.nodes.iter_mut().enumerate() {
+            if node_id != node {
+                other_node.quorum_set = QuorumSet {
+                    threshold: 2,
+                    validators: vec![node],
+                    inner_quorum_sets: vec![other_node.quorum_set.clone()],
+                };
+            }
+        }
+        let relevant_nodes = anchored_self.relevant_nodes();
+        anchored_self.shrunken(relevant_nodes)
     }
 }
 
@@ -267,6 +290,29 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn to_standard_form_normalizes_quorum_sets() {
+        let fbas = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": {
+                    "threshold": 2,
+                    "validators": ["n1"],
+                    "innerQuorumSets": [{ "threshold": 1, "validators": ["n0"] }]
+                }
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": { "threshold": 2, "validators": ["n1", "n0"] }
+            }
+        ]"#,
+        );
+        let expected = toy_standard_form_fbas();
+        let actual = fbas.to_standard_form();
+        assert_eq!(expected, actual);
+    }
+
     #[test]
     fn standard_form_is_stable() {
         use hex;
@@ -296,5 +342,9 @@ mod tests {
             standard_form_fbas_expected_hash,
             standard_form_fbas_actual_hash
         );
+        assert_eq!(
+            standard_form_fbas_expected_hash,
+            original_fbas.standard_form_hash()
+        );
     }
 }
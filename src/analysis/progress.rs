@@ -0,0 +1,325 @@
+use super::*;
+
+use std::fmt;
+use std::sync::Arc;
+
+/// Snapshot of the progress of a long-running search for node sets, as reported to a
+/// `ProgressListener`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SearchProgress {
+    /// What is being searched for, e.g., `"minimal quorums"`.
+    pub search: &'static str,
+    /// Index of the consensus cluster currently being searched.
+    pub cluster_index: usize,
+    /// Number of consensus clusters that need to be searched.
+    pub number_of_clusters: usize,
+    /// Number of sets found so far (across all clusters and before any postprocessing).
+    pub sets_found: usize,
+    /// Number of search tree nodes visited so far (across all clusters).
+    pub nodes_visited: u64,
+    /// Rough estimate (between 0 and 1) of which fraction of the search has been completed.
+    /// Assumes that all clusters and all branches of the search tree are equally expensive.
+    pub estimated_completion: f64,
+    /// Whether this is the last report for this search (because it is done or was cancelled).
+    pub finished: bool,
+}
+
+/// Receives progress updates from long-running searches. Implemented for all `Fn(&SearchProgress)`
+/// closures.
+///
+/// ## Example
+/// ```
+/// use fbas_analyzer::*;
+///
+/// let fbas = Fbas::from_json_file(std::path::Path::new("test_data/correct_trivial.json"));
+/// let listener = |progress: &SearchProgress| {
+///     if progress.finished {
+///         println!("{}: done, found {}", progress.search, progress.sets_found);
+///     }
+/// };
+/// let options = SearchOptions::new().with_progress_listener(&listener);
+/// let minimal_quorums = find_minimal_quorums_with(&fbas, &options);
+/// assert_eq!(minimal_quorums.len(), 3);
+/// ```
+pub trait ProgressListener {
+    fn on_progress(&self, progress: &SearchProgress);
+}
+impl<F: Fn(&SearchProgress)> ProgressListener for F {
+    fn on_progress(&self, progress: &SearchProgress) {
+        self(progress)
+    }
+}
+
+/// Options for running one of the `find_*_with` searches: when to give up, whom to report
+/// progress to and how many threads to use.
+///
+/// ## Example
+/// ```
+/// use fbas_analyzer::*;
+/// use std::time::Duration;
+///
+/// let fbas = Fbas::from_json_file(std::path::Path::new("test_data/correct_trivial.json"));
+/// let listener = |progress: &SearchProgress| {
+///     if progress.finished {
+///         println!("{}: done, found {}", progress.search, progress.sets_found);
+///     }
+/// };
+/// let options = SearchOptions::new()
+///     .with_cancellation(Cancellation::new().with_timeout(Duration::from_secs(3600)))
+///     .with_progress_listener(&listener)
+///     .with_number_of_threads(2);
+/// let minimal_quorums = find_minimal_quorums_with(&fbas, &options);
+/// assert_eq!(minimal_quorums.len(), 3);
+/// ```
+#[derive(Clone, Default)]
+pub struct SearchOptions<'a> {
+    cancellation: Cancellation,
+    progress_listener: Option<&'a dyn ProgressListener>,
+    number_of_threads: usize,
+}
+impl<'a> SearchOptions<'a> {
+    /// Never give up, don't report progress, use a single thread.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Stop early if `cancellation` is requested, returning only the sets found so far.
+    pub fn with_cancellation(mut self, cancellation: Cancellation) -> Self {
+        self.cancellation = cancellation;
+        self
+    }
+    /// Report the progress of the search to `listener`.
+    pub fn with_progress_listener(mut self, listener: &'a dyn ProgressListener) -> Self {
+        self.progress_listener = Some(listener);
+        self
+    }
+    /// Split the search into work items that are processed by `number_of_threads` threads. This
+    /// doesn't change the result.
+    pub fn with_number_of_threads(mut self, number_of_threads: usize) -> Self {
+        self.number_of_threads = number_of_threads;
+        self
+    }
+    pub(crate) fn monitor(&self, search: &'static str) -> SearchMonitor<'_> {
+        SearchMonitor::new(search, &self.cancellation, self.progress_listener)
+            .with_number_of_threads(self.number_of_threads)
+    }
+}
+
+/// A `ProgressListener` that can be stored in (and shared between) `Analysis` objects.
+#[derive(Clone)]
+pub(crate) struct SharedProgressListener(pub(crate) Arc<dyn ProgressListener + Send + Sync>);
+impl fmt::Debug for SharedProgressListener {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "SharedProgressListener")
+    }
+}
+
+/// How many search tree nodes to visit between two progress reports.
+const REPORT_INTERVAL: u64 = 10_000;
+
+/// Bookkeeping for a running search: checks for cancellation, counts visited search tree nodes,
+/// estimates completion and forwards all of this to an (optional) `ProgressListener`.
+pub(crate) struct SearchMonitor<'a> {
+    search: &'static str,
+    cancellation: &'a Cancellation,
+    listener: Option<&'a dyn ProgressListener>,
+    cluster_index: usize,
+    number_of_clusters: usize,
+    cluster_size: usize,
+    cluster_completion: f64,
+    sets_found: usize,
+    nodes_visited: u64,
+    number_of_threads: usize,
+    work_item: Option<WorkItem>,
+}
+/// Restricts a search to one part of the search tree, namely to the branches matching `path`
+/// (bit `i` set means: include the `i`-th candidate) in the first `split_depth` decisions.
+#[derive(Clone, Copy, Debug)]
+struct WorkItem {
+    path: usize,
+    split_depth: usize,
+}
+impl<'a> SearchMonitor<'a> {
+    pub(crate) fn new(
+        search: &'static str,
+        cancellation: &'a Cancellation,
+        listener: Option<&'a dyn ProgressListener>,
+    ) -> Self {
+        SearchMonitor {
+            search,
+            cancellation,
+            listener,
+            cluster_index: 0,
+            number_of_clusters: 0,
+            cluster_size: 0,
+            cluster_completion: 0.,
+            sets_found: 0,
+            nodes_visited: 0,
+            number_of_threads: 1,
+            work_item: None,
+        }
+    }
+    /// Allow searches to split clusters into work items processed by `number_of_threads` threads.
+    pub(crate) fn with_number_of_threads(mut self, number_of_threads: usize) -> Self {
+        self.number_of_threads = number_of_threads.max(1);
+        self
+    }
+    pub(crate) fn number_of_threads(&self) -> usize {
+        self.number_of_threads
+    }
+    pub(crate) fn cluster_size(&self) -> usize {
+        self.cluster_size
+    }
+    pub(crate) fn nodes_visited(&self) -> u64 {
+        self.nodes_visited
+    }
+    /// Returns a (thread-safe) factory for monitors that restrict the search in the current
+    /// cluster to a single work item. These monitors don't report progress.
+    pub(crate) fn work_item_monitors(&self) -> impl Fn(usize, usize) -> SearchMonitor<'a> + Sync {
+        let (search, cancellation) = (self.search, self.cancellation);
+        let (cluster_index, number_of_clusters, cluster_size) = (
+            self.cluster_index,
+            self.number_of_clusters,
+            self.cluster_size,
+        );
+        move |path, split_depth| {
+            let mut monitor = SearchMonitor::new(search, cancellation, None);
+            monitor.start_cluster(cluster_index, number_of_clusters, cluster_size);
+            monitor.work_item = Some(WorkItem { path, split_depth });
+            monitor
+        }
+    }
+    /// Account for the work done by a monitor created via `work_item_monitors`.
+    pub(crate) fn add_visited(&mut self, nodes_visited: u64) {
+        self.nodes_visited += nodes_visited;
+    }
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.cancellation.is_requested()
+    }
+    /// Call before searching the `cluster_index`-th cluster, which has `cluster_size` nodes.
+    pub(crate) fn start_cluster(
+        &mut self,
+        cluster_index: usize,
+        number_of_clusters: usize,
+        cluster_size: usize,
+    ) {
+        self.cluster_index = cluster_index;
+        self.number_of_clusters = number_of_clusters;
+        self.cluster_size = cluster_size;
+        self.cluster_completion = 0.;
+    }
+    /// Call after searching a cluster (even if the search was cut short).
+    pub(crate) fn finish_cluster(&mut self) {
+        if !self.is_cancelled() {
+            self.cluster_completion = 1.;
+        }
+        self.report(false);
+    }
+    /// Call after searching all clusters (even if the search was cut short).
+    pub(crate) fn finish(&mut self) {
+        self.report(true);
+    }
+    /// Call on each visited search tree node. Returns `false` if the search should stop.
+    pub(crate) fn visit(&mut self) -> bool {
+        self.nodes_visited += 1;
+        if self.nodes_visited.is_multiple_of(REPORT_INTERVAL) {
+            self.report(false);
+        }
+        !self.is_cancelled()
+    }
+    /// Call whenever the search finds `number` new sets.
+    pub(crate) fn found(&mut self, number: usize) {
+        self.sets_found += number;
+    }
+    /// Call before descending into the branch that includes (or excludes) the current candidate,
+    /// which has just been removed from the `unprocessed` nodes. Returns `false` if the branch
+    /// belongs to another work item and must be skipped.
+    pub(crate) fn takes_branch(&self, unprocessed: usize, include: bool) -> bool {
+        if let Some(WorkItem { path, split_depth }) = self.work_item {
+            let depth = self.cluster_size.saturating_sub(unprocessed + 1);
+            depth >= split_depth || ((path >> depth) & 1 == 1) == include
+        } else {
+            true
+        }
+    }
+    /// Call when a branch of the search tree has been fully explored or pruned. The branch is
+    /// identified by the number of nodes that are still `unprocessed` at its root.
+    pub(crate) fn complete_branch(&mut self, unprocessed: usize) {
+        let depth = self.cluster_size.saturating_sub(unprocessed);
+        self.cluster_completion += 0.5f64.powi(depth as i32);
+    }
+    fn report(&self, finished: bool) {
+        if let Some(listener) = self.listener {
+            let estimated_completion = if self.number_of_clusters == 0 {
+                1.
+            } else {
+                (self.cluster_index as f64 + self.cluster_completion.min(1.))
+                    / self.number_of_clusters as f64
+            };
+            listener.on_progress(&SearchProgress {
+                search: self.search,
+                cluster_index: self.cluster_index,
+                number_of_clusters: self.number_of_clusters,
+                sets_found: self.sets_found,
+                nodes_visited: self.nodes_visited,
+                estimated_completion,
+                finished,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    #[test]
+    fn completion_estimate_adds_up_over_complete_search_tree() {
+        let cancellation = Cancellation::new();
+        let reports = RefCell::new(vec![]);
+        let listener = |progress: &SearchProgress| reports.borrow_mut().push(*progress);
+        let mut monitor = SearchMonitor::new("test sets", &cancellation, Some(&listener));
+        monitor.start_cluster(0, 2, 2);
+        // binary search tree over two nodes, with one pruned branch
+        monitor.complete_branch(0);
+        monitor.complete_branch(0);
+        monitor.complete_branch(1);
+        monitor.found(2);
+        monitor.report(false);
+        monitor.finish_cluster();
+        monitor.start_cluster(1, 2, 1);
+        monitor.found(1);
+        monitor.finish_cluster();
+        monitor.finish();
+
+        let completions: Vec<f64> = reports
+            .borrow()
+            .iter()
+            .map(|p| p.estimated_completion)
+            .collect();
+        assert_eq!(vec![0.5, 0.5, 1., 1.], completions);
+        assert!(reports.borrow().last().unwrap().finished);
+        assert_eq!(3, reports.borrow().last().unwrap().sets_found);
+    }
+
+    #[test]
+    fn work_items_partition_top_of_search_tree() {
+        let cancellation = Cancellation::new();
+        let mut monitor = SearchMonitor::new("test sets", &cancellation, None);
+        monitor.start_cluster(0, 1, 5);
+        let work_item_monitors = monitor.work_item_monitors();
+        for path in 0..4 {
+            let work_item_monitor = work_item_monitors(path, 2);
+            // first decision (4 nodes left after popping the first candidate)
+            assert_eq!(path & 1 == 1, work_item_monitor.takes_branch(4, true));
+            assert_eq!(path & 1 == 0, work_item_monitor.takes_branch(4, false));
+            // second decision
+            assert_eq!(path & 2 == 2, work_item_monitor.takes_branch(3, true));
+            assert_eq!(path & 2 == 0, work_item_monitor.takes_branch(3, false));
+            // below the split depth, everything is explored
+            assert!(work_item_monitor.takes_branch(2, true));
+            assert!(work_item_monitor.takes_branch(2, false));
+        }
+        assert!(monitor.takes_branch(4, true) && monitor.takes_branch(4, false));
+    }
+}
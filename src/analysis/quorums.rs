@@ -2,18 +2,74 @@ use super::*;
 
 /// Find all minimal quorums in the FBAS.
 pub fn find_minimal_quorums(fbas: &Fbas) -> Vec<NodeIdSet> {
+    find_minimal_quorums_with(fbas, &SearchOptions::new())
+}
+/// Like `find_minimal_quorums`, but searches as configured via `options`. If the search is
+/// cancelled, only the minimal quorums found so far are returned.
+pub fn find_minimal_quorums_with(fbas: &Fbas, options: &SearchOptions) -> Vec<NodeIdSet> {
+    find_minimal_quorums_monitored(fbas, &mut options.monitor("minimal quorums"))
+}
+pub(crate) fn find_minimal_quorums_monitored(
+    fbas: &Fbas,
+    monitor: &mut SearchMonitor,
+) -> Vec<NodeIdSet> {
     info!("Starting to look for minimal quorums...");
-    let minimal_quorums = find_minimal_sets(fbas, minimal_quorums_finder);
+    let minimal_quorums = find_minimal_sets(fbas, |clusters, fbas| {
+        minimal_quorums_finder(clusters, fbas, monitor)
+    });
     info!("Found {} minimal quorums.", minimal_quorums.len());
     minimal_quorums
 }
 
+/// Find all minimal quorums containing `node`, i.e., all quorums containing `node` of which no
+/// proper subset is a quorum containing `node`. Among these are all minimal quorums (as found by
+/// `find_minimal_quorums`) that contain `node`.
+pub fn find_minimal_quorums_containing(fbas: &Fbas, node: NodeId) -> Vec<NodeIdSet> {
+    find_minimal_quorums_containing_monitored(
+        fbas,
+        node,
+        &mut SearchMonitor::new(
+            "minimal quorums containing node",
+            &Cancellation::new(),
+            None,
+        ),
+    )
+}
+pub(crate) fn find_minimal_quorums_containing_monitored(
+    fbas: &Fbas,
+    node: NodeId,
+    monitor: &mut SearchMonitor,
+) -> Vec<NodeIdSet> {
+    let (fbas_anchored, shrink_manager) = fbas.anchored_at(node);
+    let minimal_quorums = find_minimal_quorums_monitored(&fbas_anchored, monitor);
+    let mut minimal_quorums = shrink_manager.unshrink_sets(&minimal_quorums);
+    minimal_quorums.sort_unstable();
+    minimal_quorums.sort_by_key(|x| x.len());
+    minimal_quorums
+}
+
 /// Find at least two non-intersecting quorums. Use this function if you don't want to enumerate
 /// all minimal quorums and/or it is likely that the FBAS lacks quorum intersection and you want to
 /// stop early in such cases.
 pub fn find_nonintersecting_quorums(fbas: &Fbas) -> Option<Vec<NodeIdSet>> {
+    find_nonintersecting_quorums_with(fbas, &SearchOptions::new())
+}
+/// Like `find_nonintersecting_quorums`, but searches as configured via `options`. If the search is
+/// cancelled, `None` only means that no non-intersecting quorums were found in time.
+pub fn find_nonintersecting_quorums_with(
+    fbas: &Fbas,
+    options: &SearchOptions,
+) -> Option<Vec<NodeIdSet>> {
+    find_nonintersecting_quorums_monitored(fbas, &mut options.monitor("non-intersecting quorums"))
+}
+pub(crate) fn find_nonintersecting_quorums_monitored(
+    fbas: &Fbas,
+    monitor: &mut SearchMonitor,
+) -> Option<Vec<NodeIdSet>> {
     info!("Starting to look for potentially non-intersecting quorums...");
-    let quorums = find_sets(fbas, nonintersecting_quorums_finder);
+    let quorums = find_sets(fbas, |clusters, fbas| {
+        nonintersecting_quorums_finder(clusters, fbas, monitor)
+    });
     if quorums.len() < 2 {
         info!("Found no non-intersecting quorums.");
         None
@@ -26,34 +82,50 @@ pub fn find_nonintersecting_quorums(fbas: &Fbas) -> Option<Vec<NodeIdSet>> {
     }
 }
 
-fn minimal_quorums_finder(consensus_clusters: Vec<NodeIdSet>, fbas: &Fbas) -> Vec<NodeIdSet> {
+fn minimal_quorums_finder(
+    consensus_clusters: Vec<NodeIdSet>,
+    fbas: &Fbas,
+    monitor: &mut SearchMonitor,
+) -> Vec<NodeIdSet> {
     let mut found_quorums: Vec<NodeIdSet> = vec![];
+    let number_of_clusters = consensus_clusters.len();
     for (i, nodes) in consensus_clusters.into_iter().enumerate() {
         debug!("Finding minimal quorums in cluster {}...", i);
+        monitor.start_cluster(i, number_of_clusters, nodes.len());
 
         if let Some(symmetric_cluster) = find_symmetric_cluster_in_consensus_cluster(&nodes, fbas) {
             debug!("Cluster contains a symmetric quorum cluster! Extracting quorums...");
-            found_quorums.append(&mut symmetric_cluster.to_minimal_quorums(fbas));
+            let mut quorums = symmetric_cluster.to_minimal_quorums(fbas);
+            monitor.found(quorums.len());
+            found_quorums.append(&mut quorums);
         } else {
             debug!("Sorting nodes by rank...");
             let sorted_nodes = sort_by_rank(nodes.into_iter().collect(), fbas);
             debug!("Sorted.");
 
-            let unprocessed = sorted_nodes;
-            let mut selection = NodeIdSet::with_capacity(fbas.nodes.len());
-            let mut available = unprocessed.iter().cloned().collect();
-
             debug!("Collecting quorums...");
-            minimal_quorums_finder_step(
-                &mut unprocessed.into(),
-                &mut selection,
-                &mut available,
-                &mut found_quorums,
-                fbas,
-                true,
-            );
+            let mut quorums = search_cluster(monitor, |monitor| {
+                let unprocessed = sorted_nodes.clone();
+                let mut selection = NodeIdSet::with_capacity(fbas.nodes.len());
+                let mut available = unprocessed.iter().cloned().collect();
+                let mut found_quorums_in_cluster: Vec<NodeIdSet> = vec![];
+
+                minimal_quorums_finder_step(
+                    &mut unprocessed.into(),
+                    &mut selection,
+                    &mut available,
+                    &mut found_quorums_in_cluster,
+                    fbas,
+                    monitor,
+                    true,
+                );
+                found_quorums_in_cluster
+            });
+            found_quorums.append(&mut quorums);
         }
+        monitor.finish_cluster();
     }
+    monitor.finish();
     found_quorums
 }
 fn minimal_quorums_finder_step(
@@ -62,35 +134,57 @@ fn minimal_quorums_finder_step(
     available: &mut NodeIdSet,
     found_quorums: &mut Vec<NodeIdSet>,
     fbas: &Fbas,
+    monitor: &mut SearchMonitor,
     selection_changed: bool,
 ) {
-    if selection_changed && fbas.is_quorum(selection) {
+    if !monitor.visit() {
+        // return, keeping what we found so far
+    } else if selection_changed && fbas.is_quorum(selection) {
         if is_minimal_for_quorum(&selection, fbas) {
             found_quorums.push(selection.clone());
+            monitor.found(1);
             if found_quorums.len() % 100_000 == 0 {
                 debug!("...{} quorums found", found_quorums.len());
             }
         }
+        monitor.complete_branch(unprocessed.len());
     } else if let Some(current_candidate) = unprocessed.pop_front() {
         selection.insert(current_candidate);
 
-        minimal_quorums_finder_step(unprocessed, selection, available, found_quorums, fbas, true);
+        if monitor.takes_branch(unprocessed.len(), true) {
+            minimal_quorums_finder_step(
+                unprocessed,
+                selection,
+                available,
+                found_quorums,
+                fbas,
+                monitor,
+                true,
+            );
+        }
 
         selection.remove(current_candidate);
         available.remove(current_candidate);
 
-        if selection_satisfiable(selection, available, fbas) {
+        if !monitor.takes_branch(unprocessed.len(), false) {
+            // explored in another work item
+        } else if selection_satisfiable(selection, available, fbas) {
             minimal_quorums_finder_step(
                 unprocessed,
                 selection,
                 available,
                 found_quorums,
                 fbas,
+                monitor,
                 false,
             );
+        } else {
+            monitor.complete_branch(unprocessed.len());
         }
         unprocessed.push_front(current_candidate);
         available.insert(current_candidate);
+    } else {
+        monitor.complete_branch(unprocessed.len());
     }
 }
 
@@ -109,13 +203,17 @@ impl QuorumSet {
 fn nonintersecting_quorums_finder(
     consensus_clusters: Vec<NodeIdSet>,
     fbas: &Fbas,
+    monitor: &mut SearchMonitor,
 ) -> Vec<NodeIdSet> {
     if consensus_clusters.len() > 1 {
         debug!("More than one consensus clusters - reducing to maximal quorums.");
-        consensus_clusters
+        let quorums: Vec<NodeIdSet> = consensus_clusters
             .into_iter()
             .map(|node_set| find_satisfiable_nodes(&node_set, fbas).0)
-            .collect()
+            .collect();
+        monitor.found(quorums.len());
+        monitor.finish();
+        quorums
     } else {
         warn!("There is only one consensus cluster - there might be no non-intersecting quorums and the subsequent search might be slow.");
         let nodes = consensus_clusters.into_iter().next().unwrap_or_default();
@@ -128,12 +226,14 @@ fn nonintersecting_quorums_finder(
         let mut available: NodeIdSet = unprocessed.iter().cloned().collect();
         let mut antiselection = available.clone();
         let picks_left = unprocessed.len() / 2; // testing quorums yields no benefit
-        if let Some(intersecting_quorums) = nonintersecting_quorums_finder_step(
+        monitor.start_cluster(0, 1, unprocessed.len());
+        let quorums = if let Some(intersecting_quorums) = nonintersecting_quorums_finder_step(
             &mut unprocessed.into(),
             &mut selection,
             &mut available,
             &mut antiselection,
             fbas,
+            monitor,
             picks_left,
             true,
         ) {
@@ -143,26 +243,36 @@ fn nonintersecting_quorums_finder(
         } else {
             assert!(fbas.is_quorum(&available));
             vec![available.clone()]
-        }
+        };
+        monitor.found(quorums.len());
+        monitor.finish_cluster();
+        monitor.finish();
+        quorums
     }
 }
+#[allow(clippy::too_many_arguments)]
 fn nonintersecting_quorums_finder_step(
     unprocessed: &mut NodeIdDeque,
     selection: &mut NodeIdSet,
     available: &mut NodeIdSet,
     antiselection: &mut NodeIdSet,
     fbas: &Fbas,
+    monitor: &mut SearchMonitor,
     picks_left: usize,
     selection_changed: bool,
 ) -> Option<[NodeIdSet; 2]> {
     debug_assert!(selection.is_disjoint(&antiselection));
-    if selection_changed && fbas.is_quorum(selection) {
+    if !monitor.visit() {
+        return None;
+    } else if selection_changed && fbas.is_quorum(selection) {
         let (potential_complement, _) = find_satisfiable_nodes(&antiselection, fbas);
 
         if !potential_complement.is_empty() {
             return Some([selection.clone(), potential_complement]);
         }
+        monitor.complete_branch(unprocessed.len());
     } else if picks_left == 0 {
+        monitor.complete_branch(unprocessed.len());
         return None;
     } else if let Some(current_candidate) = unprocessed.pop_front() {
         if selection.is_empty() {
@@ -179,6 +289,7 @@ fn nonintersecting_quorums_finder_step(
             available,
             antiselection,
             fbas,
+            monitor,
             picks_left - 1,
             true,
         ) {
@@ -195,14 +306,19 @@ fn nonintersecting_quorums_finder_step(
                 available,
                 antiselection,
                 fbas,
+                monitor,
                 picks_left,
                 false,
             ) {
                 return Some(intersecting_quorums);
             }
+        } else {
+            monitor.complete_branch(unprocessed.len());
         }
         unprocessed.push_front(current_candidate);
         available.insert(current_candidate);
+    } else {
+        monitor.complete_branch(unprocessed.len());
     }
     None
 }
@@ -354,4 +470,60 @@ mod tests {
         assert!(unsatisfiable.contains(directly_unsatisfiable));
         assert!(unsatisfiable.contains(transitively_unsatisfiable));
     }
+
+    #[test]
+    fn find_minimal_quorums_containing_nodes_outside_top_tier() {
+        let fbas = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1", "n2"] }
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1", "n2"] }
+            },
+            {
+                "publicKey": "n2",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1", "n2"] }
+            },
+            {
+                "publicKey": "n3",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n3"] }
+            },
+            {
+                "publicKey": "n4",
+                "quorumSet": { "threshold": 2, "validators": ["n4", "n5"] }
+            },
+            {
+                "publicKey": "n5"
+            }
+        ]"#,
+        );
+
+        assert_eq!(
+            vec![bitset![0, 1], bitset![0, 2]],
+            find_minimal_quorums_containing(&fbas, 0)
+        );
+        assert_eq!(
+            vec![bitset![0, 1, 3], bitset![0, 2, 3]],
+            find_minimal_quorums_containing(&fbas, 3)
+        );
+        assert!(find_minimal_quorums_containing(&fbas, 4).is_empty());
+    }
+
+    #[test]
+    fn minimal_quorums_containing_node_include_minimal_quorums_with_node() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json"));
+        let minimal_quorums = find_minimal_quorums(&fbas);
+        for node in involved_nodes(&minimal_quorums).iter() {
+            let minimal_quorums_containing_node = find_minimal_quorums_containing(&fbas, node);
+            for minimal_quorum in minimal_quorums.iter().filter(|q| q.contains(node)) {
+                assert!(minimal_quorums_containing_node.contains(minimal_quorum));
+            }
+            assert!(minimal_quorums_containing_node
+                .iter()
+                .all(|quorum| quorum.contains(node) && fbas.is_quorum(quorum)));
+        }
+    }
 }
@@ -4,17 +4,26 @@ use super::*;
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Default)]
 pub struct NodeIdSetResult {
     pub(crate) node_set: NodeIdSet,
+    pub(crate) partial: bool,
 }
 impl NodeIdSetResult {
     pub(crate) fn new(node_set: NodeIdSet, shrink_manager: Option<&ShrinkManager>) -> Self {
         if let Some(shrink_manager) = shrink_manager {
             NodeIdSetResult {
                 node_set: shrink_manager.unshrink_set(&node_set),
+                partial: false,
             }
         } else {
-            NodeIdSetResult { node_set }
+            NodeIdSetResult {
+                node_set,
+                partial: false,
+            }
         }
     }
+    /// Whether the computation of this result got cancelled before it could complete.
+    pub fn is_partial(&self) -> bool {
+        self.partial
+    }
     pub fn unwrap(self) -> NodeIdSet {
         self.node_set
     }
@@ -54,6 +63,7 @@ impl NodeIdSetResult {
     pub fn merged_by_group(&self, groupings: &Groupings) -> Self {
         Self {
             node_set: groupings.merge_node_set(self.node_set.clone()),
+            partial: self.partial,
         }
     }
 }
@@ -63,14 +73,21 @@ impl NodeIdSetResult {
 pub struct NodeIdSetVecResult {
     pub(crate) node_sets: Vec<NodeIdSet>,
     pub(crate) unshrink_table: Option<Vec<NodeId>>,
+    pub(crate) partial: bool,
 }
 impl NodeIdSetVecResult {
     pub(crate) fn new(node_sets: Vec<NodeIdSet>, shrink_manager: Option<&ShrinkManager>) -> Self {
         NodeIdSetVecResult {
             node_sets,
             unshrink_table: shrink_manager.map(|m| m.unshrink_table().clone()),
+            partial: false,
         }
     }
+    /// Whether the computation of this result got cancelled before it could complete, i.e.,
+    /// whether it contains only the node sets found so far.
+    pub fn is_partial(&self) -> bool {
+        self.partial
+    }
     pub fn unwrap(mut self) -> Vec<NodeIdSet> {
         self.unshrink();
         self.node_sets
@@ -0,0 +1,270 @@
+//! Quorum intersection check via a SAT solver, similar to the one in stellar-core: we ask the
+//! solver for two disjoint quorums and, if the formula is unsatisfiable, the FBAS enjoys quorum
+//! intersection.
+
+use super::*;
+
+use varisat::{ExtendFormula, Lit, Solver};
+
+/// Find two non-intersecting quorums by encoding their existence as a SAT instance and solving it
+/// with an embedded SAT solver. Returns `None` if all quorums intersect and an empty vector if
+/// there are no quorums at all (which, as for `Analysis::has_quorum_intersection`, means that the
+/// FBAS lacks quorum intersection). Unlike `find_nonintersecting_quorums`, this works without
+/// searching through the quorums of the FBAS, which can be much faster for large, asymmetric
+/// FBASs. The returned quorums needn't be minimal.
+pub fn find_nonintersecting_quorums_via_sat(fbas: &Fbas) -> Option<Vec<NodeIdSet>> {
+    if find_quorum_via_sat(fbas).is_none() {
+        warn!("Found no quorums at all.");
+        return Some(vec![]);
+    }
+    find_nonintersecting_quorums_via_sat_despite(fbas, &bitset![])
+}
+/// Ask the SAT solver for a single quorum. Returns `None` if there is none.
+fn find_quorum_via_sat(fbas: &Fbas) -> Option<NodeIdSet> {
+    info!("Encoding search for a quorum as SAT instance...");
+    let deleted = bitset![];
+    let mut encoder = Encoder::new(fbas, &deleted);
+    let quorum = encoder.encode_quorum();
+    info!("Solving SAT instance...");
+    if encoder.solver.solve().expect("SAT solver failed!") {
+        let quorum = encoder.extract_node_set(&quorum);
+        assert!(fbas.is_quorum(&quorum));
+        Some(quorum)
+    } else {
+        None
+    }
+}
+/// Like `find_nonintersecting_quorums_via_sat`, but for the FBAS in which the `deleted` nodes have
+/// been deleted in the sense of the SCP paper, i.e., removed from all quorum slices. The returned
+/// quorums are quorums of this modified FBAS; they don't contain deleted nodes. `None` means that
+/// the FBAS enjoys quorum intersection despite `deleted`.
+pub(crate) fn find_nonintersecting_quorums_via_sat_despite(
+    fbas: &Fbas,
+    deleted: &NodeIdSet,
+) -> Option<Vec<NodeIdSet>> {
+    info!("Encoding search for non-intersecting quorums as SAT instance...");
+    let mut encoder = Encoder::new(fbas, deleted);
+    let quorum_a = encoder.encode_quorum();
+    let quorum_b = encoder.encode_quorum();
+    for (&a, &b) in quorum_a.iter().zip(quorum_b.iter()) {
+        encoder.solver.add_clause(&[!a, !b]);
+    }
+    info!("Solving SAT instance...");
+    let satisfiable = encoder.solver.solve().expect("SAT solver failed!");
+    if satisfiable {
+        let quorums = vec![
+            encoder.extract_node_set(&quorum_a),
+            encoder.extract_node_set(&quorum_b),
+        ];
+        assert!(quorums
+            .iter()
+            .all(|quorum| is_quorum_despite(quorum, fbas, deleted)));
+        assert!(quorums[0].is_disjoint(&quorums[1]));
+        warn!("Found two non-intersecting quorums.");
+        Some(quorums)
+    } else {
+        info!("SAT instance is unsatisfiable; all quorums intersect.");
+        None
+    }
+}
+
+/// Whether `node_set` is a quorum in the FBAS in which the `deleted` nodes have been deleted.
+pub(crate) fn is_quorum_despite(node_set: &NodeIdSet, fbas: &Fbas, deleted: &NodeIdSet) -> bool {
+    let with_deleted = node_set.union(deleted).collect();
+    !node_set.is_empty()
+        && node_set.is_disjoint(deleted)
+        && node_set
+            .iter()
+            .all(|node_id| fbas.nodes[node_id].is_quorum_slice(&with_deleted))
+}
+
+struct Encoder<'a> {
+    fbas: &'a Fbas,
+    deleted: &'a NodeIdSet,
+    solver: Solver<'static>,
+    true_lit: Lit,
+}
+impl<'a> Encoder<'a> {
+    fn new(fbas: &'a Fbas, deleted: &'a NodeIdSet) -> Self {
+        let mut solver = Solver::new();
+        let true_lit = solver.new_lit();
+        solver.add_clause(&[true_lit]);
+        Encoder {
+            fbas,
+            deleted,
+            solver,
+            true_lit,
+        }
+    }
+    /// Nodes whose literals (as returned by `encode_quorum`) are true in the solver's model. Call
+    /// only after a successful `solve`.
+    fn extract_node_set(&self, lits: &[Lit]) -> NodeIdSet {
+        let model: HashSet<Lit> = self
+            .solver
+            .model()
+            .expect("SAT solver returned no model!")
+            .into_iter()
+            .collect();
+        lits.iter()
+            .enumerate()
+            .filter(|(_, lit)| model.contains(lit))
+            .map(|(node_id, _)| node_id)
+            .collect()
+    }
+    /// Adds a quorum to the formula, represented by one literal per node that is true iff the node
+    /// is part of the quorum. Deleted nodes are never part of the quorum.
+    fn encode_quorum(&mut self) -> Vec<Lit> {
+        let quorum: Vec<Lit> = (0..self.fbas.nodes.len())
+            .map(|node_id| {
+                if self.deleted.contains(node_id) {
+                    !self.true_lit
+                } else {
+                    self.solver.new_lit()
+                }
+            })
+            .collect();
+        // quorums are non-empty
+        self.solver.add_clause(&quorum);
+        // nodes with the same quorum set share the encoding of "has a quorum slice in the quorum"
+        let mut slice_lits: HashMap<&QuorumSet, Lit> = HashMap::new();
+        for (node_id, node) in self.fbas.nodes.iter().enumerate() {
+            if self.deleted.contains(node_id) {
+                continue;
+            }
+            let slice_lit = if let Some(&slice_lit) = slice_lits.get(&node.quorum_set) {
+                slice_lit
+            } else {
+                let slice_lit = self.encode_quorum_slice(&node.quorum_set, &quorum);
+                slice_lits.insert(&node.quorum_set, slice_lit);
+                slice_lit
+            };
+            self.solver.add_clause(&[!quorum[node_id], slice_lit]);
+        }
+        quorum
+    }
+    /// Returns a literal that can only be true if `quorum` contains a quorum slice of `quorum_set`.
+    /// Deleted nodes count as contained in every quorum (as they are removed from all slices).
+    fn encode_quorum_slice(&mut self, quorum_set: &QuorumSet, quorum: &[Lit]) -> Lit {
+        if quorum_set.threshold == 0 {
+            return !self.true_lit; // badly configured quorum set
+        }
+        let mut members: Vec<Lit> = quorum_set
+            .validators
+            .iter()
+            .map(|&node_id| {
+                if self.deleted.contains(node_id) {
+                    self.true_lit
+                } else {
+                    quorum.get(node_id).copied().unwrap_or(!self.true_lit)
+                }
+            })
+            .collect();
+        for inner_quorum_set in quorum_set.inner_quorum_sets.iter() {
+            let inner_slice_lit = self.encode_quorum_slice(inner_quorum_set, quorum);
+            members.push(inner_slice_lit);
+        }
+        self.encode_at_least(quorum_set.threshold, &members)
+    }
+    /// Returns a literal that can only be true if at least `k` of `lits` are true. Uses a
+    /// sequential counter: after processing the `i`-th literal, `counter[j]` can only be true if
+    /// at least `j` of the first `i` literals are true.
+    fn encode_at_least(&mut self, k: usize, lits: &[Lit]) -> Lit {
+        if k > lits.len() {
+            return !self.true_lit;
+        }
+        let mut counter = vec![!self.true_lit; k + 1];
+        counter[0] = self.true_lit;
+        for (i, &lit) in lits.iter().enumerate() {
+            let mut next_counter = vec![self.true_lit];
+            for j in 1..=k {
+                if j > i + 1 {
+                    next_counter.push(!self.true_lit);
+                } else {
+                    let at_least_j = self.solver.new_lit();
+                    self.solver.add_clause(&[!at_least_j, counter[j], lit]);
+                    self.solver
+                        .add_clause(&[!at_least_j, counter[j], counter[j - 1]]);
+                    next_counter.push(at_least_j);
+                }
+            }
+            counter = next_counter;
+        }
+        counter[k]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn sat_finds_nonintersecting_quorums_in_broken_fbas() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/broken.json"));
+        let quorums = find_nonintersecting_quorums_via_sat(&fbas).unwrap();
+        assert_eq!(2, quorums.len());
+        assert!(fbas.is_quorum(&quorums[0]));
+        assert!(fbas.is_quorum(&quorums[1]));
+        assert!(quorums[0].is_disjoint(&quorums[1]));
+    }
+
+    #[test]
+    fn sat_finds_no_nonintersecting_quorums_in_correct_fbas() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json"));
+        assert_eq!(None, find_nonintersecting_quorums_via_sat(&fbas));
+    }
+
+    #[test]
+    fn sat_handles_inner_quorum_sets_and_bad_thresholds() {
+        let fbas = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": {
+                    "threshold": 2,
+                    "validators": ["n0"],
+                    "innerQuorumSets": [{ "threshold": 1, "validators": ["n1", "n2"] }]
+                }
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": { "threshold": 1, "validators": ["n1"] }
+            },
+            {
+                "publicKey": "n2",
+                "quorumSet": { "threshold": 1, "validators": ["n2"] }
+            },
+            {
+                "publicKey": "n3",
+                "quorumSet": { "threshold": 0, "validators": ["n3"] }
+            },
+            {
+                "publicKey": "n4",
+                "quorumSet": { "threshold": 1000000000000, "validators": ["n4"] }
+            }
+        ]"#,
+        );
+        let quorums = find_nonintersecting_quorums_via_sat(&fbas).unwrap();
+        assert!(quorums.iter().all(|quorum| fbas.is_quorum(quorum)));
+        assert!(quorums
+            .iter()
+            .all(|quorum| !quorum.contains(3) && !quorum.contains(4)));
+    }
+
+    #[test]
+    fn sat_finds_no_quorums_if_there_are_none() {
+        let fbas = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1"] }
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": { "threshold": 3, "validators": ["n0", "n1"] }
+            }
+        ]"#,
+        );
+        assert_eq!(Some(vec![]), find_nonintersecting_quorums_via_sat(&fbas));
+    }
+}
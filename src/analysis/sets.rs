@@ -25,7 +25,7 @@ pub fn involved_nodes(node_sets: &[NodeIdSet]) -> NodeIdSet {
 /// Does pre- and postprocessing common to most finders
 pub(crate) fn find_minimal_sets<F>(fbas: &Fbas, finder: F) -> Vec<NodeIdSet>
 where
-    F: Fn(Vec<NodeIdSet>, &Fbas) -> Vec<NodeIdSet>,
+    F: FnOnce(Vec<NodeIdSet>, &Fbas) -> Vec<NodeIdSet>,
 {
     let mut sets = find_sets(fbas, finder);
     debug_assert!(is_set_of_minimal_node_sets(&sets));
@@ -36,7 +36,7 @@ where
 /// Does preprocessing common to all finders
 pub(crate) fn find_sets<F, R>(fbas: &Fbas, finder: F) -> Vec<R>
 where
-    F: Fn(Vec<NodeIdSet>, &Fbas) -> Vec<R>,
+    F: FnOnce(Vec<NodeIdSet>, &Fbas) -> Vec<R>,
 {
     let all_nodes: NodeIdSet = (0..fbas.nodes.len()).collect();
 
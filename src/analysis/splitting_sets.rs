@@ -5,9 +5,29 @@ use itertools::Itertools;
 /// sub-FBASs. As this is probably not what you want then, you should check for quorum
 /// intersection before using this function.
 pub fn find_minimal_splitting_sets(fbas: &Fbas, minimal_quorums: &[NodeIdSet]) -> Vec<NodeIdSet> {
+    find_minimal_splitting_sets_with(fbas, minimal_quorums, &SearchOptions::new())
+}
+/// Like `find_minimal_splitting_sets`, but searches as configured via `options`. If the search is
+/// cancelled, the returned sets are minimal only with respect to each other.
+pub fn find_minimal_splitting_sets_with(
+    fbas: &Fbas,
+    minimal_quorums: &[NodeIdSet],
+    options: &SearchOptions,
+) -> Vec<NodeIdSet> {
+    find_minimal_splitting_sets_monitored(
+        fbas,
+        minimal_quorums,
+        &mut options.monitor("minimal splitting sets"),
+    )
+}
+pub(crate) fn find_minimal_splitting_sets_monitored(
+    fbas: &Fbas,
+    minimal_quorums: &[NodeIdSet],
+    monitor: &mut SearchMonitor,
+) -> Vec<NodeIdSet> {
     info!("Starting to look for minimal splitting_sets...");
     let minimal_splitting_sets = find_minimal_sets(fbas, |clusters, fbas| {
-        minimal_splitting_sets_finder(clusters, fbas, minimal_quorums)
+        minimal_splitting_sets_finder(clusters, fbas, minimal_quorums, monitor)
     });
     info!(
         "Found {} minimal splitting_sets.",
@@ -20,37 +40,46 @@ fn minimal_splitting_sets_finder(
     consensus_clusters: Vec<NodeIdSet>,
     fbas: &Fbas,
     minimal_quorums: &[NodeIdSet],
+    monitor: &mut SearchMonitor,
 ) -> Vec<NodeIdSet> {
     let mut found_splitting_sets: Vec<NodeIdSet> = vec![];
+    let number_of_clusters = consensus_clusters.len();
     for (i, nodes) in consensus_clusters.into_iter().enumerate() {
         debug!("Finding minimal splitting sets in cluster {}...", i);
+        monitor.start_cluster(i, number_of_clusters, nodes.len());
 
         if let Some(symmetric_cluster) = find_symmetric_cluster_in_consensus_cluster(&nodes, fbas) {
             debug!("Cluster contains a symmetric quorum cluster! Extracting splitting sets...");
-            found_splitting_sets.append(&mut symmetric_cluster.to_minimal_splitting_sets());
+            let mut splitting_sets = symmetric_cluster.to_minimal_splitting_sets();
+            monitor.found(splitting_sets.len());
+            found_splitting_sets.append(&mut splitting_sets);
         } else {
             debug!("Sorting nodes by rank...");
             let sorted_nodes = sort_by_rank(nodes.into_iter().collect(), fbas);
             debug!("Sorted.");
 
-            let unprocessed = sorted_nodes;
-            let mut selection = NodeIdSet::with_capacity(fbas.nodes.len());
-            let mut available = unprocessed.iter().cloned().collect();
-
-            let relevant_quorum_parts = minimal_quorums.to_vec();
-
-            let mut found_splitting_sets_in_cluster: Vec<NodeIdSet> = vec![];
-
             debug!("Collecting splitting_sets...");
-            splitting_sets_finder_step(
-                &mut unprocessed.into(),
-                &mut selection,
-                &mut available,
-                &mut found_splitting_sets_in_cluster,
-                fbas,
-                relevant_quorum_parts,
-                true,
-            );
+            let found_splitting_sets_in_cluster = search_cluster(monitor, |monitor| {
+                let unprocessed = sorted_nodes.clone();
+                let mut selection = NodeIdSet::with_capacity(fbas.nodes.len());
+                let mut available = unprocessed.iter().cloned().collect();
+
+                let relevant_quorum_parts = minimal_quorums.to_vec();
+
+                let mut found_splitting_sets_in_cluster: Vec<NodeIdSet> = vec![];
+
+                splitting_sets_finder_step(
+                    &mut unprocessed.into(),
+                    &mut selection,
+                    &mut available,
+                    &mut found_splitting_sets_in_cluster,
+                    fbas,
+                    monitor,
+                    relevant_quorum_parts,
+                    true,
+                );
+                found_splitting_sets_in_cluster
+            });
             debug!(
                 "Found {} splitting_sets. Reducing to minimal splitting sets...",
                 found_splitting_sets_in_cluster.len()
@@ -59,25 +88,64 @@ fn minimal_splitting_sets_finder(
                 found_splitting_sets_in_cluster,
             ));
         }
+        monitor.finish_cluster();
     }
+    monitor.finish();
     found_splitting_sets
 }
+/// Where `splitting_sets_finder_step` puts the splitting sets it finds.
+trait FoundSplittingSets {
+    fn add(&mut self, splitting_set: &NodeIdSet);
+    /// Whether adding nodes to a selection of size `selection_size` can still yield splitting
+    /// sets worth adding.
+    fn is_worth_extending(&self, selection_size: usize) -> bool;
+}
+/// Collects all found splitting sets.
+impl FoundSplittingSets for Vec<NodeIdSet> {
+    fn add(&mut self, splitting_set: &NodeIdSet) {
+        self.push(splitting_set.clone());
+        if self.len() % 100_000 == 0 {
+            debug!("...{} splitting_sets found", self.len());
+        }
+    }
+    fn is_worth_extending(&self, _: usize) -> bool {
+        true
+    }
+}
+/// Keeps only the smallest splitting set found so far, bounding the search by its size.
+impl FoundSplittingSets for Option<NodeIdSet> {
+    fn add(&mut self, splitting_set: &NodeIdSet) {
+        // we only ever get here with selections that are smaller than the current best
+        debug!("...found a splitting set of size {}", splitting_set.len());
+        *self = Some(splitting_set.clone());
+    }
+    fn is_worth_extending(&self, selection_size: usize) -> bool {
+        self.as_ref()
+            .is_none_or(|best| selection_size + 1 < best.len())
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 fn splitting_sets_finder_step(
     unprocessed: &mut NodeIdDeque,
     selection: &mut NodeIdSet,
     available: &mut NodeIdSet,
-    found_splitting_sets: &mut Vec<NodeIdSet>,
+    found_splitting_sets: &mut impl FoundSplittingSets,
     fbas: &Fbas,
+    monitor: &mut SearchMonitor,
     relevant_quorum_parts: Vec<NodeIdSet>,
     selection_changed: bool,
 ) {
-    if relevant_quorum_parts.len() < 2 {
-        // return
+    if !monitor.visit() {
+        // return, keeping what we found so far
+    } else if relevant_quorum_parts.len() < 2 {
+        monitor.complete_branch(unprocessed.len());
     } else if selection_changed && is_quorum_intersection(selection, fbas, &relevant_quorum_parts) {
-        found_splitting_sets.push(selection.clone());
-        if found_splitting_sets.len() % 100_000 == 0 {
-            debug!("...{} splitting_sets found", found_splitting_sets.len());
-        }
+        found_splitting_sets.add(selection);
+        monitor.found(1);
+        monitor.complete_branch(unprocessed.len());
+    } else if !found_splitting_sets.is_worth_extending(selection.len()) {
+        monitor.complete_branch(unprocessed.len());
     } else if let Some(current_candidate) = unprocessed.pop_front() {
         selection.insert(current_candidate);
 
@@ -91,15 +159,18 @@ fn splitting_sets_finder_step(
             })
             .collect();
 
-        splitting_sets_finder_step(
-            unprocessed,
-            selection,
-            available,
-            found_splitting_sets,
-            fbas,
-            relevant_quorum_parts_with_select,
-            true,
-        );
+        if monitor.takes_branch(unprocessed.len(), true) {
+            splitting_sets_finder_step(
+                unprocessed,
+                selection,
+                available,
+                found_splitting_sets,
+                fbas,
+                monitor,
+                relevant_quorum_parts_with_select,
+                true,
+            );
+        }
 
         selection.remove(current_candidate);
         available.remove(current_candidate);
@@ -109,22 +180,124 @@ fn splitting_sets_finder_step(
             .filter(|q| !q.is_disjoint(available))
             .collect();
 
-        if has_potential(selection, available, fbas) {
+        if !monitor.takes_branch(unprocessed.len(), false) {
+            // explored in another work item
+        } else if has_potential(selection, available, fbas) {
             splitting_sets_finder_step(
                 unprocessed,
                 selection,
                 available,
                 found_splitting_sets,
                 fbas,
+                monitor,
                 relevant_quorum_parts_for_available,
                 false,
             );
+        } else {
+            monitor.complete_branch(unprocessed.len());
         }
         unprocessed.push_front(current_candidate);
         available.insert(current_candidate);
+    } else {
+        monitor.complete_branch(unprocessed.len());
+    }
+}
+
+/// Find one of the smallest minimal splitting sets in the FBAS, via a branch and bound search
+/// that doesn't enumerate all minimal splitting sets. The same caveats as for
+/// `find_minimal_splitting_sets` apply if the FBAS *doesn't* enjoy quorum intersection.
+/// Returns `None` if there is no splitting set, e.g., because there is only one quorum.
+pub fn find_smallest_splitting_set(
+    fbas: &Fbas,
+    minimal_quorums: &[NodeIdSet],
+) -> Option<NodeIdSet> {
+    find_smallest_splitting_set_with(fbas, minimal_quorums, &SearchOptions::new())
+}
+/// Like `find_smallest_splitting_set`, but searches as configured via `options`. If the search is
+/// cancelled, the smallest splitting set found so far, if any, is returned.
+pub fn find_smallest_splitting_set_with(
+    fbas: &Fbas,
+    minimal_quorums: &[NodeIdSet],
+    options: &SearchOptions,
+) -> Option<NodeIdSet> {
+    find_smallest_splitting_set_monitored(
+        fbas,
+        minimal_quorums,
+        &mut options.monitor("smallest splitting set"),
+    )
+}
+pub(crate) fn find_smallest_splitting_set_monitored(
+    fbas: &Fbas,
+    minimal_quorums: &[NodeIdSet],
+    monitor: &mut SearchMonitor,
+) -> Option<NodeIdSet> {
+    info!("Starting to look for a smallest splitting set...");
+    let smallest_splitting_set = find_sets(fbas, |clusters, fbas| {
+        smallest_splitting_set_finder(clusters, fbas, minimal_quorums, monitor)
+    })
+    .pop();
+    if let Some(ref splitting_set) = smallest_splitting_set {
+        info!(
+            "Found a smallest splitting set of size {}.",
+            splitting_set.len()
+        );
     }
+    smallest_splitting_set
 }
 
+fn smallest_splitting_set_finder(
+    consensus_clusters: Vec<NodeIdSet>,
+    fbas: &Fbas,
+    minimal_quorums: &[NodeIdSet],
+    monitor: &mut SearchMonitor,
+) -> Vec<NodeIdSet> {
+    // splitting any one cluster suffices, so the best set found so far bounds all clusters
+    let mut best: Option<NodeIdSet> = None;
+    let number_of_clusters = consensus_clusters.len();
+    for (i, nodes) in consensus_clusters.into_iter().enumerate() {
+        debug!("Finding a smallest splitting set in cluster {}...", i);
+        monitor.start_cluster(i, number_of_clusters, nodes.len());
+
+        if let Some(symmetric_cluster) = find_symmetric_cluster_in_consensus_cluster(&nodes, fbas) {
+            debug!("Cluster contains a symmetric quorum cluster! Extracting splitting sets...");
+            if let Some(splitting_set) = symmetric_cluster
+                .to_minimal_splitting_sets()
+                .into_iter()
+                .min_by_key(|splitting_set| splitting_set.len())
+            {
+                if best.as_ref().is_none_or(|b| splitting_set.len() < b.len()) {
+                    best = Some(splitting_set);
+                    monitor.found(1);
+                }
+            }
+        } else {
+            debug!("Sorting nodes by rank...");
+            let sorted_nodes = sort_by_rank(nodes.into_iter().collect(), fbas);
+            debug!("Sorted.");
+
+            let unprocessed = sorted_nodes;
+            let mut selection = NodeIdSet::with_capacity(fbas.nodes.len());
+            let mut available = unprocessed.iter().cloned().collect();
+
+            let relevant_quorum_parts = minimal_quorums.to_vec();
+
+            debug!("Searching...");
+            splitting_sets_finder_step(
+                &mut unprocessed.into(),
+                &mut selection,
+                &mut available,
+                &mut best,
+                fbas,
+                monitor,
+                relevant_quorum_parts,
+                true,
+            );
+        }
+        monitor.finish_cluster();
+    }
+    monitor.finish();
+    best.into_iter().collect()
+}
 impl Node {
     fn is_slice_intersection(&self, node_set: &NodeIdSet) -> bool {
         self.quorum_set.is_slice_intersection(&node_set)
@@ -253,6 +426,88 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn find_smallest_splitting_set_in_correct() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json"));
+        let minimal_quorums = bitsetvec![{0, 1}, {0, 10}, {1, 10}];
+
+        let expected = Some(bitset![0]);
+        let actual = find_smallest_splitting_set(&fbas, &minimal_quorums);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn find_smallest_splitting_set_of_weird_fbas() {
+        let fbas = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 3, "validators": ["n0", "n1"], "innerQuorumSets": [
+                    { "threshold": 1, "validators": ["n2", "n3"] }
+                ]}
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": { "threshold": 3, "validators": ["n0", "n1"], "innerQuorumSets": [
+                    { "threshold": 1, "validators": ["n2", "n3"] }
+                ]}
+            },
+            {
+                "publicKey": "n2",
+                "quorumSet": { "threshold": 4, "validators": ["n2", "n3"], "innerQuorumSets": [
+                    { "threshold": 1, "validators": ["n0", "n1"] },
+                    { "threshold": 1, "validators": ["n4", "n5"] }
+                ]}
+            },
+            {
+                "publicKey": "n3",
+                "quorumSet": { "threshold": 4, "validators": ["n2", "n3"], "innerQuorumSets": [
+                    { "threshold": 1, "validators": ["n0", "n1"] },
+                    { "threshold": 1, "validators": ["n4", "n5"] }
+                ]}
+            },
+            {
+                "publicKey": "n4",
+                "quorumSet": { "threshold": 2, "validators": ["n2", "n3"] }
+            },
+            {
+                "publicKey": "n5",
+                "quorumSet": { "threshold": 2, "validators": ["n2", "n3"] }
+            }
+
+        ]"#,
+        );
+        let minimal_quorums = bitsetvec![{0, 1, 2, 3, 4}, {0, 1, 2, 3, 5}];
+
+        let expected = Some(bitset![0, 1, 2, 3]);
+        let actual = find_smallest_splitting_set(&fbas, &minimal_quorums);
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn find_smallest_splitting_set_if_one_quorum() {
+        let fbas = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1"] }
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1", "n2"] }
+            },
+            {
+                "publicKey": "n2",
+                "quorumSet": { "threshold": 1, "validators": [] }
+            }
+        ]"#,
+        );
+        let minimal_quorums = bitsetvec![{0, 1}];
+
+        assert_eq!(None, find_smallest_splitting_set(&fbas, &minimal_quorums));
+    }
+
     #[test]
     fn find_minimal_splitting_sets_in_different_consensus_clusters() {
         let fbas = Fbas::from_json_str(
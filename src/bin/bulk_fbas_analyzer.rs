@@ -14,7 +14,6 @@ use std::path::{Path, PathBuf};
 
 use csv::{Reader, Writer};
 use par_map::ParMap;
-use sha3::{Digest, Sha3_256};
 
 /// Bulk analyze multiple FBASs (in stellarbeat.org JSON format)
 #[derive(Debug, StructOpt)]
@@ -253,9 +252,7 @@ fn analyze(input: InputDataPoint) -> OutputDataPoint {
         let orgs_output = maybe_merge_sets(&analysis, organizations);
         let isps_output = maybe_merge_sets(&analysis, isps);
         let ctries_output = maybe_merge_sets(&analysis, countries);
-        let standard_form_hash = hex::encode(Sha3_256::digest(
-            &fbas.to_standard_form().to_json_string().into_bytes(),
-        ));
+        let standard_form_hash = fbas.standard_form_hash();
         let mut output = OutputDataPoint {
             label,
             has_quorum_intersection,
@@ -2,10 +2,19 @@ extern crate fbas_analyzer;
 
 use fbas_analyzer::*;
 
+use indicatif::{ProgressBar, ProgressStyle};
 use quicli::prelude::*;
+use sha3::{Digest, Sha3_256};
 use structopt::StructOpt;
 
-use std::path::PathBuf;
+use std::cell::RefCell;
+use std::cmp::max;
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
 
 /// Learn things about a given FBAS (parses data from stellarbeat.org)
 #[derive(Debug, StructOpt)]
@@ -14,6 +23,16 @@ struct Cli {
     /// Will use STDIN if omitted.
     nodes_path: Option<PathBuf>,
 
+    /// Read the FBAS from the JSON output of stellar-core's `quorum?transitive=true` HTTP
+    /// command instead, i.e., analyze the FBAS as seen by one validator.
+    #[structopt(long = "stellar-core")]
+    stellar_core: bool,
+
+    /// Set the quorum set of a node as configured in a stellar-core configuration file (given as
+    /// PUBLIC_KEY=PATH), adding the node if it is not yet part of the FBAS. Can be repeated.
+    #[structopt(long = "stellar-core-config", number_of_values = 1)]
+    stellar_core_configs: Vec<String>,
+
     /// Output (and find) minimal quorums.
     #[structopt(short = "q", long = "minimal-quorums")]
     minimal_quorums: bool,
@@ -36,10 +55,73 @@ struct Cli {
     #[structopt(long = "alternative-quorum-intersection-check")]
     alternative_quorum_intersection_check: bool,
 
+    /// How to check quorum intersection: "minimal-quorums" (via finding all minimal quorums) or
+    /// "sat" (via asking a SAT solver for two non-intersecting quorums, which are output if found).
+    #[structopt(
+        long = "qi-backend",
+        default_value = "minimal-quorums",
+        raw(possible_values = r#"&["minimal-quorums", "sat"]"#)
+    )]
+    qi_backend: QuorumIntersectionBackend,
+
     /// Don't check quorum intersection.
     #[structopt(long = "dont-check-quorum-intersection")]
     dont_check_quorum_intersection: bool,
 
+    /// Output (and find) the minimal quorums containing this node (given by public key) and the
+    /// minimal sets of other nodes that can block it.
+    #[structopt(long = "for-node")]
+    for_node: Option<String>,
+
+    /// Assume that these nodes are faulty and output which nodes are still intact (guaranteed to
+    /// stay safe and live) and which are befouled. Nodes can be given by public key or, if merging
+    /// by organization (or ISP, country), by the name of their group.
+    #[structopt(long = "assume-faulty", number_of_values = 1)]
+    assume_faulty: Vec<String>,
+
+    /// Output (and find) minimal DSets (minimal dispensable sets): minimal sets of nodes whose
+    /// failure can neither cause a fork nor block progress for the remaining nodes.
+    #[structopt(long = "minimal-dsets")]
+    minimal_dsets: bool,
+
+    /// Fail (with exit code 3) if there is no quorum intersection, e.g., for use in CI.
+    #[structopt(long = "require-quorum-intersection")]
+    require_quorum_intersection: bool,
+
+    /// Fail (with exit code 4) if the smallest blocking set has fewer nodes than this. Measured
+    /// after merging if nodes are merged (e.g., counts organizations with `--merge-by-org`).
+    #[structopt(long = "min-blocking-set-size")]
+    min_blocking_set_size: Option<usize>,
+
+    /// Fail (with exit code 5) if the smallest splitting set has fewer nodes than this. Measured
+    /// after merging if nodes are merged.
+    #[structopt(long = "min-splitting-set-size")]
+    min_splitting_set_size: Option<usize>,
+
+    /// Fail (with exit code 6) if the top tier has more nodes than this. Measured after merging if
+    /// nodes are merged.
+    #[structopt(long = "max-top-tier-size")]
+    max_top_tier_size: Option<usize>,
+
+    /// Stop searching after this many seconds and output what was found until then. Incomplete
+    /// results are marked as partial. Can't be combined with `--qi-backend sat`, as the SAT
+    /// solver can't be interrupted.
+    #[structopt(long = "timeout")]
+    timeout: Option<u64>,
+
+    /// Reuse analysis results cached in this file, if they belong to the same FBAS, and store all
+    /// (complete) results in it afterwards.
+    #[structopt(long = "cache")]
+    cache_path: Option<PathBuf>,
+
+    /// Number of threads to use for searching minimal quorums, blocking sets and splitting sets.
+    #[structopt(long = "threads", default_value = "1")]
+    threads: usize,
+
+    /// Show the progress of long-running searches as a progress bar on STDERR.
+    #[structopt(long = "progress")]
+    progress: bool,
+
     /// Output metrics instead of lists of node lists.
     #[structopt(short = "d", long = "describe")]
     describe: bool,
@@ -54,6 +136,16 @@ struct Cli {
     #[structopt(long = "results-only")]
     results_only: bool,
 
+    /// Output format: "text" (one result per line, interspersed with commentary) or "json" (a
+    /// single JSON document containing all results, without commentary; see the README for its
+    /// schema).
+    #[structopt(
+        long = "format",
+        default_value = "text",
+        raw(possible_values = r#"&["text", "json"]"#)
+    )]
+    format: OutputFormat,
+
     /// Merge nodes by organization - nodes from the same organization are handled as one;
     /// you must provide the path to a stellarbeat.org "organizations" JSON file.
     #[structopt(long = "merge-by-org")]
@@ -71,15 +163,252 @@ struct Cli {
 
     #[structopt(flatten)]
     verbosity: Verbosity,
+
+    #[structopt(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Debug, StructOpt)]
+enum Command {
+    /// Check the FBAS for configuration problems (references to unknown nodes, impossible
+    /// thresholds, duplicate validators, unsatisfiable nodes, ...) instead of analyzing it.
+    /// Exits with exit code 2 if there are error-severity findings.
+    #[structopt(name = "lint")]
+    Lint {
+        /// Path to JSON file describing the FBAS in stellarbeat.org "nodes" format.
+        /// Will use STDIN if omitted.
+        nodes_path: Option<PathBuf>,
+    },
+    /// Output the FBAS's trust graph (in Graphviz DOT format by default) instead of analyzing it.
+    /// Nodes are annotated with their strongly connected component, satisfiability and top tier
+    /// membership.
+    #[structopt(name = "export")]
+    Export {
+        /// Path to JSON file describing the FBAS in stellarbeat.org "nodes" format.
+        /// Will use STDIN if omitted.
+        nodes_path: Option<PathBuf>,
+
+        /// Output GraphML instead of Graphviz DOT.
+        #[structopt(long = "graphml")]
+        graphml: bool,
+
+        /// Annotate nodes with their organization; you must provide the path to a
+        /// stellarbeat.org "organizations" JSON file.
+        #[structopt(long = "organizations")]
+        organizations_path: Option<PathBuf>,
+
+        /// Highlight one of the smallest minimal blocking sets.
+        #[structopt(long = "highlight-blocking-set")]
+        highlight_blocking_set: bool,
+
+        /// Highlight one of the smallest minimal splitting sets.
+        #[structopt(long = "highlight-splitting-set")]
+        highlight_splitting_set: bool,
+    },
+    /// Compare two FBAS snapshots: output added and removed nodes, changed quorum sets, changed
+    /// organization memberships and how the sizes of the top tier, the smallest blocking set and
+    /// the smallest splitting set changed. Nodes are matched by public key.
+    #[structopt(name = "diff")]
+    Diff {
+        /// Path to JSON file describing the older FBAS in stellarbeat.org "nodes" format.
+        old_nodes_path: PathBuf,
+
+        /// Path to JSON file describing the newer FBAS in stellarbeat.org "nodes" format.
+        new_nodes_path: PathBuf,
+
+        /// Path to a stellarbeat.org "organizations" JSON file belonging to the older FBAS.
+        #[structopt(long = "old-organizations")]
+        old_organizations_path: Option<PathBuf>,
+
+        /// Path to a stellarbeat.org "organizations" JSON file belonging to the newer FBAS.
+        #[structopt(long = "new-organizations")]
+        new_organizations_path: Option<PathBuf>,
+    },
+    /// Simulate what happens if some nodes go offline: output which nodes lose their quorum
+    /// slices and go offline as a consequence (round by round), which minimal quorums survive and
+    /// whether the whole network halts.
+    #[structopt(name = "cascade")]
+    Cascade {
+        /// Path to JSON file describing the FBAS in stellarbeat.org "nodes" format.
+        /// Will use STDIN if omitted.
+        nodes_path: Option<PathBuf>,
+
+        /// Let this node go offline, given by public key or, if organizations are loaded, by
+        /// organization name. Can be repeated.
+        #[structopt(long = "fail", number_of_values = 1)]
+        fail: Vec<String>,
+
+        /// Merge nodes by organization in the output; you must provide the path to a
+        /// stellarbeat.org "organizations" JSON file.
+        #[structopt(long = "organizations")]
+        organizations_path: Option<PathBuf>,
+    },
+    /// Serve analyses via HTTP on 127.0.0.1: POST a JSON object with the FBAS (`nodes`, in
+    /// stellarbeat.org "nodes" format), optionally `organizations` (in stellarbeat.org
+    /// "organizations" format) and optionally a `timeout` in seconds to /analyze and get quorum
+    /// intersection, top tier, minimal blocking sets and minimal splitting sets back as JSON.
+    /// Complete results are kept in memory and reused for FBASs with the same standard form;
+    /// concurrent requests for the same FBAS share one analysis. `--timeout` and `--threads` set
+    /// the default timeout and the number of threads per request.
+    #[structopt(name = "serve")]
+    Serve {
+        /// Port to listen on; 0 picks a free port.
+        #[structopt(long = "port", default_value = "8000")]
+        port: u16,
+
+        /// Number of requests that are handled at the same time; further requests wait.
+        #[structopt(long = "workers", default_value = "4")]
+        workers: usize,
+
+        /// Number of analyses (of distinct FBASs) kept in memory; when full, the least recently
+        /// requested one is dropped.
+        #[structopt(long = "max-cached-analyses", default_value = "16")]
+        max_cached_analyses: usize,
+    },
+    /// Watch a directory for new or changed stellarbeat.org snapshots (files named like those
+    /// written by `scripts/get_latest_stellarbeat_data.sh`) and analyze the most recently changed
+    /// one, merging by organization if there is a matching organizations file. Snapshots whose
+    /// FBAS has the same standard form hash as the previously analyzed one are skipped. Results
+    /// are appended to a JSONL history; alerts are printed if there is no quorum intersection or
+    /// if the smallest blocking or splitting set is too small. `--timeout` and `--threads` apply
+    /// to each analysis.
+    #[structopt(name = "watch")]
+    Watch {
+        /// Directory to watch.
+        dir: PathBuf,
+
+        /// Append results to this file (one JSON object per analyzed snapshot); defaults to
+        /// `history.jsonl` in the watched directory.
+        #[structopt(long = "history")]
+        history_path: Option<PathBuf>,
+
+        /// Seconds to wait between checks for new or changed files.
+        #[structopt(long = "interval", default_value = "60")]
+        interval: u64,
+
+        /// Alert if the smallest blocking set has fewer nodes (or organizations) than this.
+        #[structopt(long = "min-blocking-set-size")]
+        min_blocking_set_size: Option<usize>,
+
+        /// Alert if the smallest splitting set has fewer nodes (or organizations) than this.
+        #[structopt(long = "min-splitting-set-size")]
+        min_splitting_set_size: Option<usize>,
+
+        /// Check the directory only once instead of watching it, e.g., for use in a cron job.
+        #[structopt(long = "once")]
+        once: bool,
+    },
 }
 
 fn main() -> CliResult {
     let args = Cli::from_args();
     args.verbosity.setup_env_logger("fbas_analyzer")?;
 
-    let fbas = load_fbas(args.nodes_path.as_ref());
+    if let Some(Command::Lint { nodes_path }) = &args.command {
+        return lint(nodes_path.as_ref());
+    }
+    if let Some(Command::Export {
+        nodes_path,
+        graphml,
+        organizations_path,
+        highlight_blocking_set,
+        highlight_splitting_set,
+    }) = &args.command
+    {
+        let mut fbas = load_fbas(nodes_path.as_ref(), args.stellar_core);
+        apply_stellar_core_configs(&mut fbas, &args.stellar_core_configs)?;
+        let organizations = maybe_load_organizations(organizations_path.as_ref(), &fbas);
+        export(
+            &fbas,
+            &organizations,
+            *graphml,
+            *highlight_blocking_set,
+            *highlight_splitting_set,
+        );
+        return Ok(());
+    }
+    if let Some(Command::Diff {
+        old_nodes_path,
+        new_nodes_path,
+        old_organizations_path,
+        new_organizations_path,
+    }) = &args.command
+    {
+        let old_fbas = load_fbas(Some(old_nodes_path), args.stellar_core);
+        let new_fbas = load_fbas(Some(new_nodes_path), args.stellar_core);
+        let old_organizations =
+            maybe_load_organizations(old_organizations_path.as_ref(), &old_fbas);
+        let new_organizations =
+            maybe_load_organizations(new_organizations_path.as_ref(), &new_fbas);
+        diff(&old_fbas, &new_fbas, &old_organizations, &new_organizations);
+        return Ok(());
+    }
+    if let Some(Command::Cascade {
+        nodes_path,
+        fail,
+        organizations_path,
+    }) = &args.command
+    {
+        let mut fbas = load_fbas(nodes_path.as_ref(), args.stellar_core);
+        apply_stellar_core_configs(&mut fbas, &args.stellar_core_configs)?;
+        let organizations = maybe_load_organizations(organizations_path.as_ref(), &fbas);
+        let failing = resolve_nodes(fail, &fbas, &organizations)?;
+        let output = Output::init(&args, &fbas, &organizations);
+        cascade(&fbas, &failing, &organizations, &output);
+        output.finish();
+        return Ok(());
+    }
+
+    if let Some(Command::Serve {
+        port,
+        workers,
+        max_cached_analyses,
+    }) = &args.command
+    {
+        return serve(
+            *port,
+            *workers,
+            *max_cached_analyses,
+            args.timeout,
+            args.threads,
+        );
+    }
+    if let Some(Command::Watch {
+        dir,
+        history_path,
+        interval,
+        min_blocking_set_size,
+        min_splitting_set_size,
+        once,
+    }) = &args.command
+    {
+        let history_path = history_path
+            .clone()
+            .unwrap_or_else(|| dir.join("history.jsonl"));
+        let alert_thresholds = (*min_blocking_set_size, *min_splitting_set_size);
+        return watch(
+            dir,
+            &history_path,
+            *interval,
+            alert_thresholds,
+            *once,
+            &args,
+        );
+    }
+
+    if args.timeout.is_some() && args.qi_backend == QuorumIntersectionBackend::Sat {
+        return Err(format_err!(
+            "--timeout can't be combined with --qi-backend sat, as the SAT solver can't be interrupted."
+        )
+        .into());
+    }
+    let mut fbas = load_fbas(args.nodes_path.as_ref(), args.stellar_core);
+    apply_stellar_core_configs(&mut fbas, &args.stellar_core_configs)?;
     let (ctry, isp, org) = extract_groupings_todos(&args);
-    let groupings = if ctry {
+    let groupings = if (ctry || isp) && args.stellar_core {
+        eprintln!("Will not merge. ISP and country data is not part of stellar-core output.");
+        None
+    } else if ctry {
         maybe_load_countries(args.nodes_path.as_ref(), &fbas)
     } else if isp {
         maybe_load_isps(args.nodes_path.as_ref(), &fbas)
@@ -88,7 +417,34 @@ fn main() -> CliResult {
     } else {
         None
     };
-    let analysis = Analysis::new(&fbas);
+    let faulty = resolve_nodes(&args.assume_faulty, &fbas, &groupings)?;
+    let for_node = if let Some(public_key) = &args.for_node {
+        let node = fbas.get_node_id(public_key);
+        if node.is_none() {
+            return Err(format_err!("Unknown node: {:?}", public_key).into());
+        }
+        node
+    } else {
+        None
+    };
+    let mut analysis = if let Some(cache_path) = &args.cache_path {
+        Analysis::with_cache(&fbas, cache_path)?
+    } else {
+        Analysis::new(&fbas)
+    };
+    if let Some(timeout) = args.timeout {
+        analysis = analysis
+            .with_cancellation(Cancellation::new().with_timeout(Duration::from_secs(timeout)));
+    }
+    if args.threads > 1 {
+        analysis = analysis.with_number_of_threads(args.threads);
+    }
+    if args.qi_backend != QuorumIntersectionBackend::MinimalQuorums {
+        analysis = analysis.with_quorum_intersection_backend(args.qi_backend);
+    }
+    if args.progress {
+        analysis = analysis.with_progress_listener(progress_bar_listener());
+    }
 
     let (q, b, s) = extract_main_todos(&args);
     let output = Output::init(&args, &fbas, &groupings);
@@ -110,6 +466,16 @@ fn main() -> CliResult {
         );
     }
 
+    if let Some(node) = for_node {
+        find_and_report_sets_for_node(&analysis, node, &groupings, &output);
+    }
+    if !args.assume_faulty.is_empty() {
+        find_and_report_intactness(&analysis, &faulty, &groupings, &output);
+    }
+    if args.minimal_dsets {
+        find_and_report_minimal_dsets(&analysis, &groupings, &output);
+    }
+
     if b {
         find_and_report_minimal_blocking_sets(&analysis, &groupings, &output);
     }
@@ -119,11 +485,286 @@ fn main() -> CliResult {
     if q || b || s {
         report_top_tier_uncondensed(&analysis, &groupings, &output);
     }
+    output.finish();
+    let failed_assertions = check_assertions(&analysis, &fbas, &groupings, &args);
+    if let Some(cache_path) = &args.cache_path {
+        analysis.save_cache(cache_path)?;
+    }
+    if analysis.is_cancelled() {
+        eprintln!("Analysis timed out; results marked as partial are incomplete.");
+    }
+    for failed_assertion in failed_assertions.iter() {
+        eprintln!("Assertion failed: {}", failed_assertion.explanation);
+    }
+    if let Some(failed_assertion) = failed_assertions.first() {
+        std::process::exit(failed_assertion.exit_code);
+    }
     Ok(())
 }
 
-fn load_fbas(o_nodes_path: Option<&PathBuf>) -> Fbas {
-    let fbas = if let Some(nodes_path) = o_nodes_path {
+const EXIT_CODE_LINT_ERRORS: i32 = 2;
+const EXIT_CODE_NO_QUORUM_INTERSECTION: i32 = 3;
+const EXIT_CODE_BLOCKING_SET_TOO_SMALL: i32 = 4;
+const EXIT_CODE_SPLITTING_SET_TOO_SMALL: i32 = 5;
+const EXIT_CODE_TOP_TIER_TOO_LARGE: i32 = 6;
+const EXIT_CODE_ASSERTION_UNDECIDED: i32 = 7;
+
+/// An assertion (`--require-quorum-intersection`, `--min-blocking-set-size`, ...) that doesn't
+/// hold or, because the analysis timed out, couldn't be checked.
+struct FailedAssertion {
+    exit_code: i32,
+    explanation: String,
+}
+impl FailedAssertion {
+    fn new(exit_code: i32, explanation: String) -> Self {
+        FailedAssertion {
+            exit_code,
+            explanation,
+        }
+    }
+}
+/// Two non-intersecting minimal quorums, no quorums at all if there are none, or `None` if the
+/// search for minimal quorums timed out before finding either.
+fn nonintersecting_minimal_quorums(
+    analysis: &Analysis,
+    fbas: &Fbas,
+    groupings: Option<&Groupings>,
+) -> Option<Vec<Vec<String>>> {
+    let minimal_quorums = analysis.minimal_quorums();
+    let partial = minimal_quorums.is_partial();
+    let node_sets = minimal_quorums.clone().unwrap();
+    let pretty_quorums = minimal_quorums.into_pretty_vec_vec(fbas, groupings);
+    let nonintersecting_pair = node_sets.iter().enumerate().find_map(|(i, quorum)| {
+        node_sets[i + 1..]
+            .iter()
+            .position(|other| quorum.is_disjoint(other))
+            .map(|j| (i, i + 1 + j))
+    });
+    if let Some((i, j)) = nonintersecting_pair {
+        Some(vec![pretty_quorums[i].clone(), pretty_quorums[j].clone()])
+    } else if node_sets.is_empty() && !partial {
+        Some(vec![])
+    } else {
+        None
+    }
+}
+/// Checks all requested assertions, returning failed ones ordered by exit code. Witness sets are
+/// given in terms of public keys or, if merging, group names.
+fn check_assertions(
+    analysis: &Analysis,
+    fbas: &Fbas,
+    groupings: &Option<Groupings>,
+    args: &Cli,
+) -> Vec<FailedAssertion> {
+    let mut failed_assertions = vec![];
+    let orgs = groupings.as_ref();
+    if args.require_quorum_intersection {
+        let sat_check = analysis.quorum_intersection_backend() == QuorumIntersectionBackend::Sat;
+        if !analysis.has_quorum_intersection() {
+            let quorums = if sat_check {
+                analysis
+                    .has_quorum_intersection_via_sat()
+                    .1
+                    .map(|quorums| quorums.into_pretty_vec_vec(fbas, orgs))
+            } else {
+                nonintersecting_minimal_quorums(analysis, fbas, orgs)
+            };
+            failed_assertions.push(match quorums {
+                Some(quorums) if quorums.is_empty() => FailedAssertion::new(
+                    EXIT_CODE_NO_QUORUM_INTERSECTION,
+                    "no quorum intersection; no quorum exists".to_string(),
+                ),
+                Some(quorums) => FailedAssertion::new(
+                    EXIT_CODE_NO_QUORUM_INTERSECTION,
+                    format!(
+                        "no quorum intersection; non-intersecting quorums: {}",
+                        to_json_string(quorums)
+                    ),
+                ),
+                None => FailedAssertion::new(
+                    EXIT_CODE_ASSERTION_UNDECIDED,
+                    "timed out before quorum intersection could be verified".to_string(),
+                ),
+            });
+        } else if !sat_check && analysis.minimal_quorums().is_partial() {
+            failed_assertions.push(FailedAssertion::new(
+                EXIT_CODE_ASSERTION_UNDECIDED,
+                "timed out before quorum intersection could be verified".to_string(),
+            ));
+        }
+    }
+    type AllSets = fn(&Analysis) -> NodeIdSetVecResult;
+    type SmallestSet = fn(&Analysis) -> Option<NodeIdSetResult>;
+    let min_size_assertions: [(&str, Option<usize>, i32, AllSets, SmallestSet); 2] = [
+        (
+            "blocking",
+            args.min_blocking_set_size,
+            EXIT_CODE_BLOCKING_SET_TOO_SMALL,
+            Analysis::minimal_blocking_sets,
+            Analysis::smallest_blocking_set,
+        ),
+        (
+            "splitting",
+            args.min_splitting_set_size,
+            EXIT_CODE_SPLITTING_SET_TOO_SMALL,
+            Analysis::minimal_splitting_sets,
+            Analysis::smallest_splitting_set,
+        ),
+    ];
+    for (name, min_size, exit_code, all_sets, smallest_set) in min_size_assertions {
+        if let Some(min_size) = min_size {
+            // merging can make smaller sets out of larger ones, so we need all of them
+            let (smallest_set, partial) = if let Some(orgs) = orgs {
+                let sets = all_sets(analysis);
+                let partial = sets.is_partial();
+                let smallest_set = sets
+                    .merged_by_group(orgs)
+                    .minimal_sets()
+                    .into_pretty_vec_vec(fbas, Some(orgs))
+                    .into_iter()
+                    .min_by_key(|set| set.len());
+                (smallest_set, partial)
+            } else {
+                let set = smallest_set(analysis);
+                let partial = set
+                    .as_ref()
+                    .map_or(analysis.is_cancelled(), |set| set.is_partial());
+                (set.map(|set| set.into_pretty_vec(fbas, None)), partial)
+            };
+            let found_so_far = smallest_set.as_ref().map_or_else(
+                || "none found so far".to_string(),
+                |set| format!("smallest found so far has size {}", set.len()),
+            );
+            if let Some(smallest_set) = smallest_set.filter(|set| set.len() < min_size) {
+                failed_assertions.push(FailedAssertion::new(
+                    exit_code,
+                    format!(
+                        "smallest {} set has size {} (less than {}): {}",
+                        name,
+                        smallest_set.len(),
+                        min_size,
+                        to_json_string(smallest_set)
+                    ),
+                ));
+            } else if partial {
+                failed_assertions.push(FailedAssertion::new(
+                    EXIT_CODE_ASSERTION_UNDECIDED,
+                    format!(
+                        "timed out before the smallest {} set could be found ({}, required \
+                         are at least {})",
+                        name, found_so_far, min_size
+                    ),
+                ));
+            }
+        }
+    }
+    if let Some(max_size) = args.max_top_tier_size {
+        let top_tier = analysis.top_tier();
+        let partial = top_tier.is_partial();
+        let top_tier = if let Some(orgs) = orgs {
+            top_tier.merged_by_group(orgs)
+        } else {
+            top_tier
+        };
+        let size = top_tier.len();
+        // a partial top tier contains only some of the top tier nodes
+        if size > max_size {
+            failed_assertions.push(FailedAssertion::new(
+                EXIT_CODE_TOP_TIER_TOO_LARGE,
+                format!(
+                    "top tier has size {} (more than {}): {}",
+                    size,
+                    max_size,
+                    to_json_string(top_tier.into_pretty_vec(fbas, orgs))
+                ),
+            ));
+        } else if partial {
+            failed_assertions.push(FailedAssertion::new(
+                EXIT_CODE_ASSERTION_UNDECIDED,
+                format!(
+                    "timed out before the whole top tier could be found (found {} of at most {} \
+                     allowed so far)",
+                    size, max_size
+                ),
+            ));
+        }
+    }
+    failed_assertions.sort_by_key(|failed_assertion| failed_assertion.exit_code);
+    failed_assertions
+}
+fn to_json_string(value: impl serde::Serialize) -> String {
+    serde_json::to_string(&value).expect("Error serializing witness")
+}
+
+fn lint(o_nodes_path: Option<&PathBuf>) -> CliResult {
+    let findings = if let Some(nodes_path) = o_nodes_path {
+        eprintln!("Reading FBAS JSON from file...");
+        Fbas::validate_json_file(nodes_path)?
+    } else {
+        eprintln!("Reading FBAS JSON from STDIN...");
+        let mut json = String::new();
+        std::io::Read::read_to_string(&mut std::io::stdin(), &mut json)?;
+        Fbas::validate_json_str(&json)?
+    };
+    for finding in findings.iter() {
+        println!("{}", finding);
+    }
+    let count = |severity| findings.iter().filter(|f| f.severity == severity).count();
+    eprintln!(
+        "Found {} errors, {} warnings and {} infos.",
+        count(Severity::Error),
+        count(Severity::Warning),
+        count(Severity::Info)
+    );
+    if count(Severity::Error) > 0 {
+        std::process::exit(EXIT_CODE_LINT_ERRORS);
+    }
+    Ok(())
+}
+
+fn export(
+    fbas: &Fbas,
+    organizations: &Option<Groupings>,
+    graphml: bool,
+    highlight_blocking_set: bool,
+    highlight_splitting_set: bool,
+) {
+    let analysis = Analysis::new(fbas);
+    let smallest = |sets: Vec<NodeIdSet>| sets.into_iter().min_by_key(|set| set.len());
+    let highlighted = if highlight_blocking_set {
+        eprintln!("Highlighting one of the smallest minimal blocking sets...");
+        smallest(analysis.minimal_blocking_sets().unwrap())
+    } else if highlight_splitting_set {
+        eprintln!("Highlighting one of the smallest minimal splitting sets...");
+        smallest(analysis.minimal_splitting_sets().unwrap())
+    } else {
+        None
+    };
+    let annotations = TrustGraphAnnotations {
+        organizations: organizations.as_ref(),
+        top_tier: Some(analysis.top_tier().unwrap()),
+        highlighted,
+    };
+    if graphml {
+        print!("{}", fbas.to_graphml_string(&annotations));
+    } else {
+        print!("{}", fbas.to_dot_string(&annotations));
+    }
+}
+
+fn load_fbas(o_nodes_path: Option<&PathBuf>, stellar_core: bool) -> Fbas {
+    let fbas = if stellar_core {
+        if let Some(nodes_path) = o_nodes_path {
+            eprintln!("Reading stellar-core quorum JSON from file...");
+            Fbas::from_stellar_core_quorum_json_file(nodes_path)
+        } else {
+            eprintln!("Reading stellar-core quorum JSON from STDIN...");
+            let mut json = String::new();
+            std::io::Read::read_to_string(&mut std::io::stdin(), &mut json)
+                .expect("Error reading from STDIN");
+            Fbas::from_stellar_core_quorum_json_str(&json)
+        }
+    } else if let Some(nodes_path) = o_nodes_path {
         eprintln!("Reading FBAS JSON from file...");
         Fbas::from_json_file(nodes_path)
     } else {
@@ -133,6 +774,48 @@ fn load_fbas(o_nodes_path: Option<&PathBuf>) -> Fbas {
     eprintln!("Loaded FBAS with {} nodes.", fbas.number_of_nodes());
     fbas
 }
+fn apply_stellar_core_configs(fbas: &mut Fbas, stellar_core_configs: &[String]) -> CliResult {
+    for stellar_core_config in stellar_core_configs.iter() {
+        let mut parts = stellar_core_config.splitn(2, '=');
+        let (public_key, config_path) = match (parts.next(), parts.next()) {
+            (Some(public_key), Some(config_path)) => (public_key, PathBuf::from(config_path)),
+            _ => {
+                return Err(format_err!(
+                    "Expected PUBLIC_KEY=PATH for --stellar-core-config, got {:?}",
+                    stellar_core_config
+                )
+                .into())
+            }
+        };
+        eprintln!(
+            "Reading quorum set of node {} from stellar-core configuration file...",
+            public_key
+        );
+        fbas.try_add_stellar_core_config_file(public_key, &config_path)?;
+    }
+    if !stellar_core_configs.is_empty() {
+        eprintln!("FBAS now has {} nodes.", fbas.number_of_nodes());
+    }
+    Ok(())
+}
+/// Resolve public keys and group names to node IDs.
+fn resolve_nodes(
+    names: &[String],
+    fbas: &Fbas,
+    groupings: &Option<Groupings>,
+) -> Result<NodeIdSet, quicli::prelude::Error> {
+    let mut nodes = NodeIdSet::new();
+    for name in names.iter() {
+        if let Some(node_id) = fbas.get_node_id(name) {
+            nodes.insert(node_id);
+        } else if let Some(group) = groupings.as_ref().and_then(|g| g.get_by_name(name)) {
+            nodes.extend(group.validators().iter().copied());
+        } else {
+            return Err(format_err!("Unknown node or group: {:?}", name));
+        }
+    }
+    Ok(nodes)
+}
 fn maybe_load_organizations<'a>(
     o_organizations_path: Option<&PathBuf>,
     fbas: &'a Fbas,
@@ -171,6 +854,35 @@ fn maybe_load_countries<'a>(
         None
     }
 }
+/// Renders the progress of each search as a progress bar on STDERR, which is removed again once
+/// the search is finished.
+fn progress_bar_listener() -> impl ProgressListener + Send + Sync {
+    let current_bar: Mutex<Option<ProgressBar>> = Mutex::new(None);
+    move |progress: &SearchProgress| {
+        let mut current_bar = current_bar.lock().unwrap();
+        let bar = current_bar.get_or_insert_with(|| {
+            ProgressBar::new(1000)
+                .with_style(
+                    ProgressStyle::with_template("{prefix}: [{wide_bar}] {percent}% ({msg})")
+                        .unwrap(),
+                )
+                .with_prefix(progress.search)
+        });
+        bar.set_message(format!(
+            "cluster {}/{}, {} found, {} search tree nodes visited",
+            progress.cluster_index + 1,
+            progress.number_of_clusters,
+            progress.sets_found,
+            progress.nodes_visited
+        ));
+        bar.set_position((progress.estimated_completion * 1000.) as u64);
+        if progress.finished {
+            bar.finish_and_clear();
+            *current_bar = None;
+        }
+    }
+}
+
 fn extract_main_todos(args: &Cli) -> (bool, bool, bool) {
     if args.all {
         (true, true, true)
@@ -231,23 +943,39 @@ fn check_and_report_if_has_quorum_intersection(
     output: &Output,
     alternative_check: bool,
 ) {
-    let has_quorum_intersection = if alternative_check {
-        output.comment("Alternative quorum intersection check...");
-        let ((has_quorum_intersection, quorums), duration) =
-            timed!(analysis.has_quorum_intersection_via_alternative_check());
+    let sat_check = analysis.quorum_intersection_backend() == QuorumIntersectionBackend::Sat;
+    let (has_quorum_intersection, partial) = if alternative_check || sat_check {
+        let ((has_quorum_intersection, quorums), duration) = if alternative_check {
+            output.comment("Alternative quorum intersection check...");
+            timed!(analysis.has_quorum_intersection_via_alternative_check())
+        } else {
+            output.comment("SAT-based quorum intersection check...");
+            timed!(analysis.has_quorum_intersection_via_sat())
+        };
         output.timed_result("has_quorum_intersection", has_quorum_intersection, duration);
         if let Some(nonintersecting_quorums) = quorums {
             output.result("nonintersecting_quorums", nonintersecting_quorums);
         }
-        has_quorum_intersection
+        (
+            has_quorum_intersection,
+            alternative_check && analysis.is_cancelled(),
+        )
     } else {
         do_time_and_report!(
             "has_quorum_intersection",
             analysis.has_quorum_intersection(),
             output
         );
-        analysis.has_quorum_intersection() // from cache
+        // both from cache
+        (
+            analysis.has_quorum_intersection(),
+            analysis.minimal_quorums().is_partial(),
+        )
     };
+    if partial {
+        output.mark_partial("has_quorum_intersection");
+        output.comment("(Timed out before all quorums could be checked.)");
+    }
     if has_quorum_intersection {
         output.comment("\nAll quorums intersect 👍\n");
     } else {
@@ -257,6 +985,81 @@ fn check_and_report_if_has_quorum_intersection(
         );
     }
 }
+fn find_and_report_sets_for_node(
+    analysis: &Analysis,
+    node: NodeId,
+    groupings: &Option<Groupings>,
+    output: &Output,
+) {
+    let (quorums, duration) = timed!(analysis.minimal_quorums_containing(node));
+    let number_of_quorums = quorums.len();
+    let quorums = if let Some(ref orgs) = groupings {
+        quorums.merged_by_group(orgs).minimal_sets()
+    } else {
+        quorums
+    };
+    output.timed_result("minimal_quorums_containing_node", quorums, duration);
+    output.comment(&format!(
+        "\nWe found {} minimal quorums containing the node.\n",
+        number_of_quorums
+    ));
+    let (blocking_sets, duration) = timed!(analysis.minimal_blocking_sets_for(node));
+    let number_of_blocking_sets = blocking_sets.len();
+    let blocking_sets = if let Some(ref orgs) = groupings {
+        blocking_sets.merged_by_group(orgs).minimal_sets()
+    } else {
+        blocking_sets
+    };
+    output.timed_result("minimal_blocking_sets_for_node", blocking_sets, duration);
+    output.comment(&format!(
+        "\nWe found {} minimal blocking sets for the node. Control over any of these sets is \
+            sufficient to compromise the liveness of the node.\n",
+        number_of_blocking_sets
+    ));
+}
+fn find_and_report_intactness(
+    analysis: &Analysis,
+    faulty: &NodeIdSet,
+    groupings: &Option<Groupings>,
+    output: &Output,
+) {
+    output.comment(&format!(
+        "Assuming that {} nodes are faulty...",
+        faulty.len()
+    ));
+    let maybe_merge = |result: NodeIdSetResult| {
+        if let Some(ref orgs) = groupings {
+            result.merged_by_group(orgs)
+        } else {
+            result
+        }
+    };
+    do_time_and_report!(
+        "intact_nodes",
+        maybe_merge(analysis.intact_nodes(faulty)),
+        output
+    );
+    do_time_and_report!(
+        "befouled_nodes",
+        maybe_merge(analysis.befouled_nodes(faulty)),
+        output
+    );
+    output.comment("(Intact nodes stay safe and live no matter what the faulty nodes do.)");
+    do_time_and_report!(
+        "has_quorum_intersection_despite_faulty",
+        analysis.has_quorum_intersection_despite(faulty),
+        output
+    );
+    do_time_and_report!(
+        "has_quorum_availability_despite_faulty",
+        analysis.has_quorum_availability_despite(faulty),
+        output
+    );
+    output.comment(
+        "(Whether the faulty nodes can't cause forks, respectively block progress, as defined in \
+         the SCP paper. By this definition, unsatisfiable nodes count as blocked.)\n",
+    );
+}
 fn find_and_report_symmetric_clusters(
     analysis: &Analysis,
     groupings: &Option<Groupings>,
@@ -291,6 +1094,559 @@ fn find_and_report_minimal_quorums(
         analysis.minimal_quorums().len()
     ));
 }
+fn diff(
+    old_fbas: &Fbas,
+    new_fbas: &Fbas,
+    old_organizations: &Option<Groupings>,
+    new_organizations: &Option<Groupings>,
+) {
+    let fbas_diff = old_fbas.diff(new_fbas);
+    print_diff_result("added_nodes", &fbas_diff.added_nodes);
+    print_diff_result("removed_nodes", &fbas_diff.removed_nodes);
+    print_diff_result("changed_quorum_sets", &fbas_diff.changed_quorum_sets);
+    if let (Some(old_orgs), Some(new_orgs)) = (old_organizations, new_organizations) {
+        print_diff_result("changed_organization_memberships", &old_orgs.diff(new_orgs));
+    }
+    eprintln!(
+        "Found {} added nodes, {} removed nodes and {} changed quorum sets.",
+        fbas_diff.added_nodes.len(),
+        fbas_diff.removed_nodes.len(),
+        fbas_diff.changed_quorum_sets.len()
+    );
+
+    let old_analysis = Analysis::new(old_fbas);
+    let new_analysis = Analysis::new(new_fbas);
+    let old_top_tier = old_analysis.top_tier();
+    let new_top_tier = new_analysis.top_tier();
+    print_diff_result("top_tier_size", &(old_top_tier.len(), new_top_tier.len()));
+    let to_public_keys = |top_tier: &NodeIdSetResult, fbas: &Fbas| -> BTreeSet<String> {
+        top_tier
+            .clone()
+            .into_pretty_vec(fbas, None)
+            .into_iter()
+            .collect()
+    };
+    let old_top_tier = to_public_keys(&old_top_tier, old_fbas);
+    let new_top_tier = to_public_keys(&new_top_tier, new_fbas);
+    print_diff_result(
+        "top_tier_added_nodes",
+        &new_top_tier.difference(&old_top_tier).collect::<Vec<_>>(),
+    );
+    print_diff_result(
+        "top_tier_removed_nodes",
+        &old_top_tier.difference(&new_top_tier).collect::<Vec<_>>(),
+    );
+    let size = |set: Option<NodeIdSetResult>| set.map(|set| set.len());
+    print_diff_result(
+        "smallest_blocking_set_size",
+        &(
+            size(old_analysis.smallest_blocking_set()),
+            size(new_analysis.smallest_blocking_set()),
+        ),
+    );
+    print_diff_result(
+        "smallest_splitting_set_size",
+        &(
+            size(old_analysis.smallest_splitting_set()),
+            size(new_analysis.smallest_splitting_set()),
+        ),
+    );
+}
+/// Prints `value` as single-line JSON; sizes are printed as `[old, new]`.
+fn print_diff_result(result_name: &str, value: &impl serde::Serialize) {
+    println!(
+        "{}: {}",
+        result_name,
+        serde_json::to_string(value).expect("Error formatting as JSON")
+    );
+}
+fn cascade(fbas: &Fbas, failing: &NodeIdSet, organizations: &Option<Groupings>, output: &Output) {
+    let analysis = Analysis::new(fbas);
+    let rounds = analysis.cascade_rounds(failing);
+    let number_of_rounds = rounds.len();
+    let rounds = if let Some(ref orgs) = organizations {
+        rounds.merged_by_group(orgs)
+    } else {
+        rounds
+    };
+    output.result("cascade_rounds", rounds);
+    output.comment(&format!(
+        "(Nodes that went offline in each of {} rounds, starting with the failing nodes.)",
+        number_of_rounds
+    ));
+    let surviving_nodes = analysis.surviving_nodes(failing);
+    let surviving_nodes = if let Some(ref orgs) = organizations {
+        surviving_nodes.merged_by_group(orgs)
+    } else {
+        surviving_nodes
+    };
+    output.result("surviving_nodes", surviving_nodes);
+    do_time_maybe_merge_and_report!(
+        "surviving_minimal_quorums",
+        analysis.surviving_minimal_quorums(failing),
+        organizations,
+        output
+    );
+    let network_halts = analysis.cascade_halts_network(failing);
+    output.result("network_halts", network_halts);
+    if network_halts {
+        output.comment("\nNo quorum survives; the network halts 👎\n");
+    } else {
+        output.comment("\nSome quorums survive; the surviving nodes can still make progress 👍\n");
+    }
+}
+fn serve(
+    port: u16,
+    number_of_workers: usize,
+    max_cached_analyses: usize,
+    default_timeout: Option<u64>,
+    number_of_threads: usize,
+) -> CliResult {
+    let server = tiny_http::Server::http(("127.0.0.1", port))
+        .map_err(|e| format_err!("Error starting HTTP server: {}", e))?;
+    eprintln!("Listening on http://{}", server.server_addr());
+    let server = Arc::new(server);
+    let analyses = Arc::new(Mutex::new(AnalysisPool::new(max_cached_analyses)));
+    let workers: Vec<_> = (0..max(number_of_workers, 1))
+        .map(|_| {
+            let server = Arc::clone(&server);
+            let analyses = Arc::clone(&analyses);
+            std::thread::spawn(move || {
+                for request in server.incoming_requests() {
+                    handle_request(request, &analyses, default_timeout, number_of_threads);
+                }
+            })
+        })
+        .collect();
+    for worker in workers {
+        worker.join().ok();
+    }
+    Ok(())
+}
+fn handle_request(
+    mut request: tiny_http::Request,
+    analyses: &Mutex<AnalysisPool>,
+    default_timeout: Option<u64>,
+    number_of_threads: usize,
+) {
+    let result = match (request.method(), request.url()) {
+        (tiny_http::Method::Post, "/analyze") => {
+            let mut body = String::new();
+            std::io::Read::read_to_string(request.as_reader(), &mut body)
+                .map_err(|e| (400, format!("Error reading request: {}", e)))
+                .and_then(|_| {
+                    analyze_request(&body, analyses, default_timeout, number_of_threads)
+                        .map_err(|e| (400, e))
+                })
+        }
+        (_, "/analyze") => Err((405, "Use POST for /analyze".to_string())),
+        (_, url) => Err((404, format!("Not found: {}", url))),
+    };
+    let (status_code, response) = match result {
+        Ok(response) => (200, response),
+        Err((status_code, error)) => (status_code, serde_json::json!({ "error": error })),
+    };
+    eprintln!("{} {} -> {}", request.method(), request.url(), status_code);
+    let response = tiny_http::Response::from_string(response.to_string())
+        .with_status_code(status_code)
+        .with_header(
+            tiny_http::Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..]).unwrap(),
+        );
+    if let Err(e) = request.respond(response) {
+        eprintln!("Error sending response: {}", e);
+    }
+}
+#[derive(serde::Deserialize)]
+struct AnalyzeRequest {
+    nodes: serde_json::Value,
+    #[serde(default)]
+    organizations: Option<serde_json::Value>,
+    #[serde(default)]
+    timeout: Option<u64>,
+}
+/// The analyses kept by `serve`, one per standard form hash. Holds at most `capacity` of them,
+/// dropping the least recently used one when full; requests still working with a dropped
+/// `Analysis` keep it alive until they are done.
+struct AnalysisPool {
+    capacity: usize,
+    analyses: HashMap<String, PooledAnalysis>,
+    uses: u64,
+}
+struct PooledAnalysis {
+    analysis: Arc<Analysis>,
+    timeout: Option<u64>,
+    last_use: u64,
+}
+impl AnalysisPool {
+    fn new(capacity: usize) -> Self {
+        AnalysisPool {
+            capacity: max(capacity, 1),
+            analyses: HashMap::new(),
+            uses: 0,
+        }
+    }
+    /// The analysis for `fbas_hash` with the timeout it was started with, and whether it was
+    /// already there; otherwise inserts the one made by `new_analysis`.
+    fn get_or_insert_with(
+        &mut self,
+        fbas_hash: &str,
+        timeout: Option<u64>,
+        new_analysis: impl FnOnce() -> Analysis,
+    ) -> (Arc<Analysis>, Option<u64>, bool) {
+        self.uses += 1;
+        if let Some(pooled) = self.analyses.get_mut(fbas_hash) {
+            pooled.last_use = self.uses;
+            return (Arc::clone(&pooled.analysis), pooled.timeout, true);
+        }
+        if self.analyses.len() >= self.capacity {
+            let least_recently_used = self
+                .analyses
+                .iter()
+                .min_by_key(|(_, pooled)| pooled.last_use)
+                .map(|(fbas_hash, _)| fbas_hash.clone())
+                .unwrap();
+            self.analyses.remove(&least_recently_used);
+        }
+        let analysis = Arc::new(new_analysis());
+        self.analyses.insert(
+            fbas_hash.to_string(),
+            PooledAnalysis {
+                analysis: Arc::clone(&analysis),
+                timeout,
+                last_use: self.uses,
+            },
+        );
+        (analysis, timeout, false)
+    }
+    /// Removes `analysis` unless it has already been replaced.
+    fn remove(&mut self, fbas_hash: &str, analysis: &Arc<Analysis>) {
+        if self
+            .analyses
+            .get(fbas_hash)
+            .is_some_and(|pooled| Arc::ptr_eq(&pooled.analysis, analysis))
+        {
+            self.analyses.remove(fbas_hash);
+        }
+    }
+}
+/// Requests for FBASs with the same standard form hash share one `Analysis`, so that concurrent
+/// requests compute each result only once. Its results refer to the FBAS of the request that
+/// created it; this makes no difference for the output, which identifies nodes by public key or
+/// organization name. The timeout of that first request applies to all requests that wait for its
+/// computations and is returned as `timeout`; if results end up partial, the `Analysis` is
+/// discarded again.
+fn analyze_request(
+    body: &str,
+    analyses: &Mutex<AnalysisPool>,
+    default_timeout: Option<u64>,
+    number_of_threads: usize,
+) -> Result<serde_json::Value, String> {
+    let request: AnalyzeRequest =
+        serde_json::from_str(body).map_err(|e| format!("Invalid request: {}", e))?;
+    let fbas = Fbas::try_from_json_str(&request.nodes.to_string()).map_err(|e| e.to_string())?;
+    let fbas_hash = fbas.standard_form_hash();
+    let requested_timeout = request.timeout.or(default_timeout);
+    let (analysis, timeout, cache_hit) =
+        analyses
+            .lock()
+            .unwrap()
+            .get_or_insert_with(&fbas_hash, requested_timeout, || {
+                let mut analysis = Analysis::new(&fbas);
+                if let Some(timeout) = requested_timeout {
+                    analysis = analysis.with_cancellation(
+                        Cancellation::new().with_timeout(Duration::from_secs(timeout)),
+                    );
+                }
+                if number_of_threads > 1 {
+                    analysis = analysis.with_number_of_threads(number_of_threads);
+                }
+                analysis
+            });
+    let fbas = analysis.fbas();
+    let organizations = request
+        .organizations
+        .map(|orgs| Groupings::try_organizations_from_json_str(&orgs.to_string(), fbas))
+        .transpose()
+        .map_err(|e| e.to_string())?;
+
+    let has_quorum_intersection = analysis.has_quorum_intersection();
+    let mut top_tier = analysis.top_tier();
+    let mut minimal_blocking_sets = analysis.minimal_blocking_sets();
+    let mut minimal_splitting_sets = analysis.minimal_splitting_sets();
+    if let Some(ref orgs) = organizations {
+        top_tier = top_tier.merged_by_group(orgs);
+        minimal_blocking_sets = minimal_blocking_sets.merged_by_group(orgs).minimal_sets();
+        minimal_splitting_sets = minimal_splitting_sets.merged_by_group(orgs).minimal_sets();
+    }
+    let partial = top_tier.is_partial()
+        || minimal_blocking_sets.is_partial()
+        || minimal_splitting_sets.is_partial();
+    if partial {
+        analyses.lock().unwrap().remove(&fbas_hash, &analysis);
+    }
+    Ok(serde_json::json!({
+        "fbas_hash": fbas_hash,
+        "cache_hit": cache_hit,
+        "timeout": timeout,
+        "partial": partial,
+        "has_quorum_intersection": has_quorum_intersection,
+        "top_tier": top_tier.into_pretty_vec(fbas, organizations.as_ref()),
+        "minimal_blocking_sets":
+            minimal_blocking_sets.into_pretty_vec_vec(fbas, organizations.as_ref()),
+        "minimal_splitting_sets":
+            minimal_splitting_sets.into_pretty_vec_vec(fbas, organizations.as_ref()),
+    }))
+}
+/// Remembers when files were last modified, to find new or changed snapshots.
+struct SnapshotWatcher {
+    dir: PathBuf,
+    modified: HashMap<PathBuf, SystemTime>,
+}
+impl SnapshotWatcher {
+    fn new(dir: &Path) -> Self {
+        SnapshotWatcher {
+            dir: dir.to_path_buf(),
+            modified: HashMap::new(),
+        }
+    }
+    /// The most recently modified nodes file among those that are new or changed (or whose
+    /// organizations file is new or changed) since the last call, if any, plus the organizations
+    /// file belonging to it, if it exists.
+    fn poll(&mut self) -> std::io::Result<Option<(PathBuf, Option<PathBuf>)>> {
+        let mut changed_nodes_files = vec![];
+        for entry in std::fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            let file_name = match path.file_name().and_then(|name| name.to_str()) {
+                Some(file_name) if file_name.ends_with(".json") => file_name.to_string(),
+                _ => continue,
+            };
+            let modified = match std::fs::metadata(&path).and_then(|metadata| metadata.modified()) {
+                Ok(modified) => modified,
+                // e.g., deleted in the meantime
+                Err(_) => continue,
+            };
+            if self.modified.insert(path.clone(), modified) == Some(modified) {
+                continue;
+            }
+            if file_name.contains("nodes") {
+                changed_nodes_files.push((modified, path));
+            } else if file_name.contains("organizations") {
+                let nodes_path = self.dir.join(file_name.replace("organizations", "nodes"));
+                if let Ok(nodes_modified) =
+                    std::fs::metadata(&nodes_path).and_then(|metadata| metadata.modified())
+                {
+                    changed_nodes_files.push((max(modified, nodes_modified), nodes_path));
+                }
+            }
+        }
+        Ok(changed_nodes_files
+            .into_iter()
+            .max()
+            .map(|(_, nodes_path)| {
+                let file_name = nodes_path.file_name().unwrap().to_string_lossy();
+                let organizations_path = self.dir.join(file_name.replace("nodes", "organizations"));
+                (
+                    nodes_path,
+                    Some(organizations_path).filter(|path| path.exists()),
+                )
+            }))
+    }
+}
+fn watch(
+    dir: &Path,
+    history_path: &Path,
+    interval: u64,
+    alert_thresholds: (Option<usize>, Option<usize>),
+    once: bool,
+    args: &Cli,
+) -> CliResult {
+    // continue where we left off, so that restarting doesn't trigger a new analysis
+    let mut last_record: Option<serde_json::Value> = std::fs::read_to_string(history_path)
+        .ok()
+        .and_then(|history| serde_json::from_str(history.lines().last()?).ok());
+    let mut watcher = SnapshotWatcher::new(dir);
+    eprintln!("Watching {:?} for new or changed snapshots...", dir);
+    loop {
+        if let Some((nodes_path, organizations_path)) = watcher.poll()? {
+            match analyze_snapshot(
+                &nodes_path,
+                organizations_path.as_ref(),
+                &last_record,
+                alert_thresholds,
+                args,
+            ) {
+                Ok(Some(record)) => {
+                    let mut history = std::fs::OpenOptions::new()
+                        .create(true)
+                        .append(true)
+                        .open(history_path)?;
+                    std::io::Write::write_all(&mut history, format!("{}\n", record).as_bytes())?;
+                    eprintln!("Appended results to {:?}.", history_path);
+                    last_record = Some(record);
+                }
+                Ok(None) => {
+                    eprintln!("FBAS in {:?} is unchanged; skipping analysis.", nodes_path);
+                }
+                // the snapshot might not be completely written yet; we'll retry once it changes
+                Err(e) => eprintln!("Skipping {:?}: {}", nodes_path, e),
+            }
+        }
+        if once {
+            return Ok(());
+        }
+        std::thread::sleep(Duration::from_secs(interval));
+    }
+}
+/// Analyzes the snapshot and prints alerts, returning the history record; `None` if both the
+/// FBAS and the organizations file are the same as those of `last_record` and its results were
+/// complete.
+fn analyze_snapshot(
+    nodes_path: &Path,
+    organizations_path: Option<&PathBuf>,
+    last_record: &Option<serde_json::Value>,
+    (min_blocking_set_size, min_splitting_set_size): (Option<usize>, Option<usize>),
+    args: &Cli,
+) -> Result<Option<serde_json::Value>, fbas_analyzer::Error> {
+    let fbas = Fbas::try_from_json_file(nodes_path)?;
+    let fbas_hash = fbas.standard_form_hash();
+    let organizations_json = organizations_path
+        .map(|path| {
+            std::fs::read_to_string(path).map_err(|source| fbas_analyzer::Error::Io {
+                path: Some(path.clone()),
+                source,
+            })
+        })
+        .transpose()?;
+    let organizations_hash = organizations_json
+        .as_ref()
+        .map(|json| hex::encode(Sha3_256::digest(json.as_bytes())));
+    if let Some(record) = last_record {
+        if record["fbas_hash"].as_str() == Some(fbas_hash.as_str())
+            && record["organizations_hash"].as_str() == organizations_hash.as_deref()
+            && record["partial"] == false
+        {
+            return Ok(None);
+        }
+    }
+    let organizations = organizations_json
+        .map(|json| Groupings::try_organizations_from_json_str(&json, &fbas))
+        .transpose()?;
+    eprintln!("Analyzing {:?}...", nodes_path);
+    let mut analysis = Analysis::new(&fbas);
+    if let Some(timeout) = args.timeout {
+        analysis = analysis
+            .with_cancellation(Cancellation::new().with_timeout(Duration::from_secs(timeout)));
+    }
+    if args.threads > 1 {
+        analysis = analysis.with_number_of_threads(args.threads);
+    }
+    let has_quorum_intersection = analysis.has_quorum_intersection();
+    let top_tier = analysis.top_tier();
+    let mut partial = analysis.minimal_quorums().is_partial() || top_tier.is_partial();
+    let (top_tier_size, smallest_blocking_set_size, smallest_splitting_set_size) =
+        if let Some(ref orgs) = organizations {
+            // `min` would report size 0 if no sets were found
+            let mut smallest = |sets: NodeIdSetVecResult| {
+                partial |= sets.is_partial();
+                Some(sets.merged_by_group(orgs).minimal_sets())
+                    .filter(|sets| !sets.is_empty())
+                    .map(|sets| sets.min())
+            };
+            let smallest_blocking_set_size = smallest(analysis.minimal_blocking_sets());
+            let smallest_splitting_set_size = smallest(analysis.minimal_splitting_sets());
+            (
+                top_tier.merged_by_group(orgs).len(),
+                smallest_blocking_set_size,
+                smallest_splitting_set_size,
+            )
+        } else {
+            let mut size = |set: Option<NodeIdSetResult>| {
+                partial |= set
+                    .as_ref()
+                    .map_or(analysis.is_cancelled(), |set| set.is_partial());
+                set.map(|set| set.len())
+            };
+            let smallest_blocking_set_size = size(analysis.smallest_blocking_set());
+            let smallest_splitting_set_size = size(analysis.smallest_splitting_set());
+            (
+                top_tier.len(),
+                smallest_blocking_set_size,
+                smallest_splitting_set_size,
+            )
+        };
+
+    let mut alerts = vec![];
+    if !has_quorum_intersection {
+        let had_quorum_intersection = last_record
+            .as_ref()
+            .is_some_and(|record| record["has_quorum_intersection"] == true);
+        alerts.push(if had_quorum_intersection {
+            "quorum intersection lost".to_string()
+        } else {
+            "no quorum intersection".to_string()
+        });
+    }
+    for (name, size, threshold) in [
+        (
+            "blocking",
+            smallest_blocking_set_size,
+            min_blocking_set_size,
+        ),
+        (
+            "splitting",
+            smallest_splitting_set_size,
+            min_splitting_set_size,
+        ),
+    ] {
+        if let (Some(size), Some(threshold)) = (size, threshold) {
+            if size < threshold {
+                alerts.push(format!(
+                    "smallest {} set has size {} (below {})",
+                    name, size, threshold
+                ));
+            }
+        }
+    }
+    for alert in alerts.iter() {
+        println!("ALERT ({}): {}", nodes_path.display(), alert);
+    }
+
+    Ok(Some(serde_json::json!({
+        "timestamp": SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or(0),
+        "nodes_file": nodes_path,
+        "organizations_file": organizations_path,
+        "fbas_hash": fbas_hash,
+        "organizations_hash": organizations_hash,
+        "partial": partial,
+        "has_quorum_intersection": has_quorum_intersection,
+        "top_tier_size": top_tier_size,
+        "smallest_blocking_set_size": smallest_blocking_set_size,
+        "smallest_splitting_set_size": smallest_splitting_set_size,
+        "alerts": alerts,
+    })))
+}
+fn find_and_report_minimal_dsets(
+    analysis: &Analysis,
+    groupings: &Option<Groupings>,
+    output: &Output,
+) {
+    let (minimal_dsets, duration) = timed!(analysis.minimal_dsets());
+    let number_of_minimal_dsets = minimal_dsets.len();
+    let minimal_dsets = if let Some(ref orgs) = groupings {
+        minimal_dsets.merged_by_group(orgs).minimal_sets()
+    } else {
+        minimal_dsets
+    };
+    output.timed_result("minimal_dsets", minimal_dsets, duration);
+    output.comment(&format!(
+        "\nWe found {} minimal DSets (minimal dispensable sets). Even if all nodes in one of \
+            these sets fail, the remaining nodes stay safe and live.\n",
+        number_of_minimal_dsets
+    ));
+}
 fn find_and_report_minimal_blocking_sets(
     analysis: &Analysis,
     groupings: &Option<Groupings>,
@@ -346,6 +1702,27 @@ fn report_top_tier_uncondensed(
     );
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum OutputFormat {
+    Text,
+    Json,
+}
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            _ => Err(format!("unknown output format: {}", s)),
+        }
+    }
+}
+
+/// Version of the document written by `--format json`; to be increased with each change that
+/// could break its consumers.
+const JSON_REPORT_SCHEMA_VERSION: u64 = 1;
+
 #[derive(Clone)]
 struct Output<'a> {
     results_only: bool,
@@ -353,10 +1730,15 @@ struct Output<'a> {
     describe: bool,
     fbas: &'a Fbas,
     groupings: &'a Option<Groupings<'a>>,
+    /// Collects all results if the output format is JSON; shared between clones so that they all
+    /// contribute to the same document.
+    json_report: Option<Rc<RefCell<serde_json::Map<String, serde_json::Value>>>>,
+    merged_by: Option<&'static str>,
 }
 impl<'a> Output<'a> {
     fn init(args: &Cli, fbas: &'a Fbas, groupings: &'a Option<Groupings>) -> Self {
-        let results_only = args.results_only;
+        let json = args.format == OutputFormat::Json;
+        let results_only = args.results_only || json;
         let output_pretty = args.output_pretty;
         let describe = args.describe;
         if !results_only {
@@ -374,12 +1756,39 @@ impl<'a> Output<'a> {
                 );
             }
         }
+        // same precedence as in `extract_groupings_todos`
+        let merged_by = if groupings.is_none() {
+            None
+        } else if args.ctry_merge {
+            Some("country")
+        } else if args.isp_merge {
+            Some("isp")
+        } else {
+            Some("organization")
+        };
         Self {
             results_only,
             output_pretty,
             describe,
             fbas,
             groupings,
+            json_report: if json { Some(Rc::default()) } else { None },
+            merged_by,
+        }
+    }
+    /// Prints the JSON document, if the output format is JSON. Call once all results are in.
+    fn finish(&self) {
+        if let Some(json_report) = &self.json_report {
+            let report = serde_json::json!({
+                "schema_version": JSON_REPORT_SCHEMA_VERSION,
+                "merged_by": self.merged_by,
+                "pretty": self.output_pretty,
+                "results": *json_report.borrow(),
+            });
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&report).expect("Error formatting as JSON")
+            );
         }
     }
     fn comment(&self, comment: &str) {
@@ -395,24 +1804,37 @@ impl<'a> Output<'a> {
     fn timed_result(
         &self,
         result_name: &str,
-        result: impl AnalysisResult,
+        result: impl AnalysisResult + Clone,
         duration: timing::Duration,
     ) {
         self.result(result_name, result);
-        println!(
-            "{}_analysis_duration: {}s",
-            result_name,
-            duration.as_secs_f64()
-        );
+        if let Some(json_report) = &self.json_report {
+            json_report.borrow_mut()[result_name]["duration_secs"] =
+                serde_json::json!(duration.as_secs_f64());
+        } else {
+            println!(
+                "{}_analysis_duration: {}s",
+                result_name,
+                duration.as_secs_f64()
+            );
+        }
     }
-    fn result(&self, result_name: &str, result: impl AnalysisResult) {
-        if self.describe {
+    fn result(&self, result_name: &str, result: impl AnalysisResult + Clone) {
+        if self.json_report.is_some() {
+            self.json_result(result_name, result, !self.describe);
+        } else if self.describe {
+            self.partial_marker(result_name, &result);
             println!("{}: {}", result_name, result.into_describe_string());
         } else {
             self.result_uncondensed(result_name, result);
         }
     }
-    fn result_uncondensed(&self, result_name: &str, result: impl AnalysisResult) {
+    fn result_uncondensed(&self, result_name: &str, result: impl AnalysisResult + Clone) {
+        if self.json_report.is_some() {
+            self.json_result(result_name, result, true);
+            return;
+        }
+        self.partial_marker(result_name, &result);
         let result_string = if self.output_pretty {
             result.into_pretty_string(self.fbas, self.groupings.as_ref())
         } else {
@@ -420,4 +1842,49 @@ impl<'a> Output<'a> {
         };
         println!("{}: {}", result_name, result_string);
     }
+    fn partial_marker(&self, result_name: &str, result: &impl AnalysisResult) {
+        if result.is_partial() {
+            println!("{}_partial: true", result_name);
+        }
+    }
+    /// Marks an already output result as partial.
+    fn mark_partial(&self, result_name: &str) {
+        if let Some(json_report) = &self.json_report {
+            json_report.borrow_mut()[result_name]["partial"] = true.into();
+        } else {
+            println!("{}_partial: true", result_name);
+        }
+    }
+    /// Adds `result` to the JSON document; its (full) value is left out if `with_value` is false.
+    fn json_result(
+        &self,
+        result_name: &str,
+        result: impl AnalysisResult + Clone,
+        with_value: bool,
+    ) {
+        // all results are formatted as JSON anyway
+        let parse = |json: String| -> serde_json::Value {
+            serde_json::from_str(&json).expect("Error parsing result as JSON")
+        };
+        let partial = result.is_partial();
+        let id_value = serde_json::to_value(&result).expect("Error formatting as JSON");
+        let description = parse(result.clone().into_describe_string());
+        let mut entry = serde_json::Map::new();
+        // results that can't be condensed (such as booleans) always keep their value
+        if with_value || description == id_value {
+            let value = if self.output_pretty {
+                parse(result.into_pretty_string(self.fbas, self.groupings.as_ref()))
+            } else {
+                id_value
+            };
+            entry.insert("value".to_string(), value);
+        }
+        entry.insert("partial".to_string(), partial.into());
+        entry.insert("description".to_string(), description);
+        self.json_report
+            .as_ref()
+            .unwrap()
+            .borrow_mut()
+            .insert(result_name.to_string(), entry.into());
+    }
 }
@@ -0,0 +1,265 @@
+use super::*;
+
+/// Quorum set in terms of public keys, for constructing FBASs via `FbasBuilder`.
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct PublicKeyQuorumSet {
+    pub threshold: usize,
+    pub validators: Vec<PublicKey>,
+    pub inner_quorum_sets: Vec<PublicKeyQuorumSet>,
+}
+impl PublicKeyQuorumSet {
+    /// Quorum set requiring `threshold` out of `validators`.
+    pub fn new(threshold: usize, validators: &[&str]) -> Self {
+        PublicKeyQuorumSet {
+            threshold,
+            validators: validators.iter().map(|&pk| pk.to_string()).collect(),
+            inner_quorum_sets: vec![],
+        }
+    }
+    /// Adds an inner quorum set; note that this doesn't change the threshold.
+    pub fn with_inner_quorum_set(mut self, inner_quorum_set: PublicKeyQuorumSet) -> Self {
+        self.inner_quorum_sets.push(inner_quorum_set);
+        self
+    }
+    /// Quorum set with one inner quorum set per organization, each requiring a simple majority
+    /// of the organization's validators, like the quorum sets of the Stellar network's top tier.
+    /// `threshold` is the number of organizations required.
+    pub fn stellar_like(threshold: usize, organizations: &[&[&str]]) -> Self {
+        let inner_quorum_sets = organizations
+            .iter()
+            .map(|validators| PublicKeyQuorumSet::new(validators.len() / 2 + 1, validators))
+            .collect();
+        PublicKeyQuorumSet {
+            threshold,
+            validators: vec![],
+            inner_quorum_sets,
+        }
+    }
+    fn to_quorum_set(&self, pk_to_id: &HashMap<PublicKey, NodeId>) -> QuorumSet {
+        let mut validators: Vec<NodeId> = self
+            .validators
+            .iter()
+            .filter_map(|pk| pk_to_id.get(pk))
+            .copied()
+            .collect();
+        let mut inner_quorum_sets: Vec<QuorumSet> = self
+            .inner_quorum_sets
+            .iter()
+            .map(|inner_quorum_set| inner_quorum_set.to_quorum_set(pk_to_id))
+            .collect();
+        validators.sort_unstable();
+        inner_quorum_sets.sort_unstable();
+        QuorumSet {
+            threshold: self.threshold,
+            validators,
+            inner_quorum_sets,
+        }
+    }
+}
+
+/// Builds an `Fbas` from nodes and quorum sets given in terms of public keys, with helpers for
+/// common topologies. Node IDs are assigned in the order in which nodes are added. As when
+/// reading FBASs from JSON, validators that don't belong to any added node are ignored.
+///
+/// ## Example
+/// ```
+/// use fbas_analyzer::{Analysis, FbasBuilder, PublicKeyQuorumSet};
+///
+/// let fbas = FbasBuilder::new()
+///     .symmetric_top_tier(&["a", "b", "c"], 2)
+///     .leaf_nodes(&["x", "y"])
+///     .node("z", PublicKeyQuorumSet::new(1, &["x"]))
+///     .build();
+///
+/// assert_eq!(6, fbas.number_of_nodes());
+/// assert_eq!(Some(3), fbas.get_node_id("x"));
+/// assert_eq!(vec!["a", "b", "c"], Analysis::new(&fbas).top_tier().into_pretty_vec(&fbas, None));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct FbasBuilder {
+    nodes: Vec<(PublicKey, PublicKeyQuorumSet)>,
+    top_tier_quorum_set: Option<PublicKeyQuorumSet>,
+    last_tier: Vec<PublicKey>,
+}
+impl FbasBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Adds a node with the given quorum set.
+    pub fn node(mut self, public_key: &str, quorum_set: PublicKeyQuorumSet) -> Self {
+        self.nodes.push((public_key.to_string(), quorum_set));
+        self
+    }
+    /// Adds a top tier in which each node requires `threshold` out of all top tier nodes.
+    pub fn symmetric_top_tier(self, public_keys: &[&str], threshold: usize) -> Self {
+        self.top_tier(public_keys, PublicKeyQuorumSet::new(threshold, public_keys))
+    }
+    /// Adds a top tier made up of `organizations` in which each node uses a Stellar-like quorum
+    /// set requiring `threshold` organizations (see `PublicKeyQuorumSet::stellar_like`).
+    pub fn stellar_like_top_tier(self, organizations: &[&[&str]], threshold: usize) -> Self {
+        let public_keys: Vec<&str> = organizations.concat();
+        self.top_tier(
+            &public_keys,
+            PublicKeyQuorumSet::stellar_like(threshold, organizations),
+        )
+    }
+    /// Adds a tier of nodes that each require `threshold` out of the nodes of the previously
+    /// added tier (top tier or tier added via `tier`).
+    pub fn tier(mut self, public_keys: &[&str], threshold: usize) -> Self {
+        assert!(
+            !self.last_tier.is_empty(),
+            "A tier needs a previous tier to trust!"
+        );
+        let previous_tier: Vec<&str> = self.last_tier.iter().map(|pk| pk.as_str()).collect();
+        let quorum_set = PublicKeyQuorumSet::new(threshold, &previous_tier);
+        self = self.nodes_with_quorum_set(public_keys, &quorum_set);
+        self.last_tier = public_keys.iter().map(|&pk| pk.to_string()).collect();
+        self
+    }
+    /// Adds nodes that trust the top tier, i.e., that use the same quorum set as the nodes of the
+    /// most recently added top tier.
+    pub fn leaf_nodes(self, public_keys: &[&str]) -> Self {
+        let quorum_set = self
+            .top_tier_quorum_set
+            .clone()
+            .expect("Leaf nodes need a top tier to trust!");
+        self.nodes_with_quorum_set(public_keys, &quorum_set)
+    }
+    /// Panics if a public key was added more than once.
+    pub fn build(self) -> Fbas {
+        let mut fbas = Fbas::new();
+        for (public_key, _) in self.nodes.iter() {
+            fbas.add_node(Node::new(public_key.clone()));
+        }
+        for (node_id, (_, quorum_set)) in self.nodes.iter().enumerate() {
+            fbas.nodes[node_id].quorum_set = quorum_set.to_quorum_set(&fbas.pk_to_id);
+        }
+        fbas
+    }
+    fn top_tier(mut self, public_keys: &[&str], quorum_set: PublicKeyQuorumSet) -> Self {
+        self = self.nodes_with_quorum_set(public_keys, &quorum_set);
+        self.top_tier_quorum_set = Some(quorum_set);
+        self.last_tier = public_keys.iter().map(|&pk| pk.to_string()).collect();
+        self
+    }
+    fn nodes_with_quorum_set(
+        mut self,
+        public_keys: &[&str],
+        quorum_set: &PublicKeyQuorumSet,
+    ) -> Self {
+        for &public_key in public_keys.iter() {
+            self = self.node(public_key, quorum_set.clone());
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builder_matches_json() {
+        let expected = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1"] }
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": {
+                    "threshold": 2,
+                    "validators": ["n1"],
+                    "innerQuorumSets": [{ "threshold": 1, "validators": ["n0", "unknown"] }]
+                }
+            }
+        ]"#,
+        );
+        let actual = FbasBuilder::new()
+            .node("n0", PublicKeyQuorumSet::new(2, &["n1", "n0"]))
+            .node(
+                "n1",
+                PublicKeyQuorumSet::new(2, &["n1"])
+                    .with_inner_quorum_set(PublicKeyQuorumSet::new(1, &["n0", "unknown"])),
+            )
+            .build();
+        assert_eq!(expected, actual);
+        assert_eq!(Some(1), actual.get_node_id("n1"));
+    }
+
+    #[test]
+    fn stellar_like_top_tier_uses_organization_inner_quorum_sets() {
+        let fbas = FbasBuilder::new()
+            .stellar_like_top_tier(
+                &[&["a0", "a1", "a2"], &["b0", "b1", "b2"], &["c0", "c1"]],
+                2,
+            )
+            .build();
+        let expected = QuorumSet {
+            threshold: 2,
+            validators: vec![],
+            inner_quorum_sets: vec![
+                QuorumSet {
+                    threshold: 2,
+                    validators: vec![0, 1, 2],
+                    inner_quorum_sets: vec![],
+                },
+                QuorumSet {
+                    threshold: 2,
+                    validators: vec![3, 4, 5],
+                    inner_quorum_sets: vec![],
+                },
+                QuorumSet {
+                    threshold: 2,
+                    validators: vec![6, 7],
+                    inner_quorum_sets: vec![],
+                },
+            ],
+        };
+        assert_eq!(8, fbas.number_of_nodes());
+        assert!((0..8).all(|node_id| fbas.get_quorum_set(node_id) == Some(expected.clone())));
+        assert!(fbas.is_quorum(&bitset![0, 1, 3, 4]));
+        assert!(!fbas.is_quorum(&bitset![0, 1, 2, 3]));
+    }
+
+    #[test]
+    fn tiers_trust_previous_tier_and_leaf_nodes_trust_top_tier() {
+        let fbas = FbasBuilder::new()
+            .symmetric_top_tier(&["t0", "t1", "t2"], 2)
+            .tier(&["s0", "s1"], 1)
+            .tier(&["r0"], 2)
+            .leaf_nodes(&["l0"])
+            .build();
+        let quorum_set = |public_key| {
+            fbas.get_quorum_set(fbas.get_node_id(public_key).unwrap())
+                .unwrap()
+        };
+        assert_eq!(
+            QuorumSet {
+                threshold: 1,
+                validators: vec![0, 1, 2],
+                inner_quorum_sets: vec![],
+            },
+            quorum_set("s1")
+        );
+        assert_eq!(
+            QuorumSet {
+                threshold: 2,
+                validators: vec![3, 4],
+                inner_quorum_sets: vec![],
+            },
+            quorum_set("r0")
+        );
+        assert_eq!(quorum_set("t0"), quorum_set("l0"));
+    }
+
+    #[test]
+    #[should_panic]
+    fn build_panics_on_duplicate_public_key() {
+        FbasBuilder::new()
+            .symmetric_top_tier(&["n0", "n1"], 2)
+            .node("n0", PublicKeyQuorumSet::new(1, &["n0"]))
+            .build();
+    }
+}
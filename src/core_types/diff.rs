@@ -0,0 +1,315 @@
+use super::*;
+
+/// Differences between two FBAS snapshots, such as two daily stellarbeat.org snapshots. Nodes are
+/// matched by public key, as node IDs can differ between snapshots.
+#[derive(Clone, Eq, PartialEq, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FbasDiff {
+    pub added_nodes: Vec<PublicKey>,
+    pub removed_nodes: Vec<PublicKey>,
+    pub changed_quorum_sets: Vec<QuorumSetChange>,
+}
+/// How the quorum set of one node changed. Cosmetic changes that don't change the quorum set's
+/// normalized form (see `QuorumSet::normalized`) aren't reported. Validator deltas take into
+/// account the validators of inner quorum sets as well; a change that only restructures inner
+/// quorum sets is reported without threshold or validator deltas.
+#[derive(Clone, Eq, PartialEq, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuorumSetChange {
+    pub public_key: PublicKey,
+    pub old_threshold: usize,
+    pub new_threshold: usize,
+    pub added_validators: Vec<PublicKey>,
+    pub removed_validators: Vec<PublicKey>,
+}
+/// A node that belongs to a different group (or to no group anymore) in the newer snapshot.
+#[derive(Clone, Eq, PartialEq, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MembershipChange {
+    pub public_key: PublicKey,
+    pub old_group: Option<String>,
+    pub new_group: Option<String>,
+}
+
+impl FbasDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added_nodes.is_empty()
+            && self.removed_nodes.is_empty()
+            && self.changed_quorum_sets.is_empty()
+    }
+}
+
+impl Fbas {
+    /// What changed from `self` to the newer snapshot `other`. For changes in organization (or
+    /// ISP, country) membership, see `Groupings::diff`.
+    pub fn diff(&self, other: &Fbas) -> FbasDiff {
+        let added_nodes = other
+            .nodes
+            .iter()
+            .filter(|node| self.get_node_id(&node.public_key).is_none())
+            .map(|node| node.public_key.clone())
+            .collect();
+        let mut removed_nodes = vec![];
+        let mut changed_quorum_sets = vec![];
+        for node in self.nodes.iter() {
+            if let Some(other_node_id) = other.get_node_id(&node.public_key) {
+                let other_quorum_set = &other.nodes[other_node_id].quorum_set;
+                if CanonicalQuorumSet::new(&node.quorum_set, self)
+                    != CanonicalQuorumSet::new(other_quorum_set, other)
+                {
+                    changed_quorum_sets.push(QuorumSetChange::new(
+                        &node.public_key,
+                        (&node.quorum_set, self),
+                        (other_quorum_set, other),
+                    ));
+                }
+            } else {
+                removed_nodes.push(node.public_key.clone());
+            }
+        }
+        FbasDiff {
+            added_nodes,
+            removed_nodes,
+            changed_quorum_sets,
+        }
+    }
+}
+
+impl QuorumSetChange {
+    fn new(
+        public_key: &str,
+        (old_quorum_set, old_fbas): (&QuorumSet, &Fbas),
+        (new_quorum_set, new_fbas): (&QuorumSet, &Fbas),
+    ) -> Self {
+        let to_public_keys = |quorum_set: &QuorumSet, fbas: &Fbas| -> BTreeSet<PublicKey> {
+            quorum_set
+                .contained_nodes()
+                .iter()
+                .map(|node_id| fbas.nodes[node_id].public_key.clone())
+                .collect()
+        };
+        let old_validators = to_public_keys(old_quorum_set, old_fbas);
+        let new_validators = to_public_keys(new_quorum_set, new_fbas);
+        QuorumSetChange {
+            public_key: public_key.to_string(),
+            old_threshold: old_quorum_set.threshold,
+            new_threshold: new_quorum_set.threshold,
+            added_validators: new_validators
+                .difference(&old_validators)
+                .cloned()
+                .collect(),
+            removed_validators: old_validators
+                .difference(&new_validators)
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+impl<'fbas> Groupings<'fbas> {
+    /// Nodes contained in both underlying FBASs whose group changed from `self` to `other`.
+    pub fn diff(&self, other: &Groupings) -> Vec<MembershipChange> {
+        let group_name = |groupings: &Groupings, node_id| {
+            groupings
+                .get_by_member(node_id)
+                .map(|grouping| grouping.name.clone())
+        };
+        let mut membership_changes = vec![];
+        for (node_id, node) in self.fbas.nodes.iter().enumerate() {
+            if let Some(other_node_id) = other.fbas.get_node_id(&node.public_key) {
+                let old_group = group_name(self, node_id);
+                let new_group = group_name(other, other_node_id);
+                if old_group != new_group {
+                    membership_changes.push(MembershipChange {
+                        public_key: node.public_key.clone(),
+                        old_group,
+                        new_group,
+                    });
+                }
+            }
+        }
+        membership_changes
+    }
+}
+
+/// Normalized quorum set (see `QuorumSet::normalized`) in terms of public keys, for comparing
+/// quorum sets across FBASs.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
+struct CanonicalQuorumSet {
+    threshold: usize,
+    validators: Vec<PublicKey>,
+    inner_quorum_sets: Vec<CanonicalQuorumSet>,
+}
+impl CanonicalQuorumSet {
+    fn new(quorum_set: &QuorumSet, fbas: &Fbas) -> Self {
+        Self::from_normalized(&quorum_set.normalized(), fbas)
+    }
+    fn from_normalized(quorum_set: &QuorumSet, fbas: &Fbas) -> Self {
+        let mut validators: Vec<PublicKey> = quorum_set
+            .validators
+            .iter()
+            .map(|&node_id| fbas.nodes[node_id].public_key.clone())
+            .collect();
+        validators.sort_unstable();
+        let mut inner_quorum_sets: Vec<CanonicalQuorumSet> = quorum_set
+            .inner_quorum_sets
+            .iter()
+            .map(|inner_quorum_set| CanonicalQuorumSet::from_normalized(inner_quorum_set, fbas))
+            .collect();
+        inner_quorum_sets.sort_unstable();
+        CanonicalQuorumSet {
+            threshold: quorum_set.threshold,
+            validators,
+            inner_quorum_sets,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn fbas_has_no_diff_to_itself() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json"));
+        let diff = fbas.diff(&fbas);
+        assert!(diff.is_empty());
+        assert_eq!(FbasDiff::default(), diff);
+    }
+
+    #[test]
+    fn diff_matches_nodes_by_public_key() {
+        let old_fbas = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1", "n2"] }
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1", "n2"] }
+            },
+            {
+                "publicKey": "n2",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1", "n2"] }
+            }
+        ]"#,
+        );
+        let new_fbas = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "n3",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n2"] }
+            },
+            {
+                "publicKey": "n2",
+                "quorumSet": {
+                    "threshold": 2,
+                    "validators": ["n2", "n1"],
+                    "innerQuorumSets": [{ "threshold": 1, "validators": ["n0"] }]
+                }
+            },
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 3, "validators": ["n0", "n2", "n3"] }
+            }
+        ]"#,
+        );
+        let expected = FbasDiff {
+            added_nodes: vec!["n3".to_string()],
+            removed_nodes: vec!["n1".to_string()],
+            changed_quorum_sets: vec![
+                QuorumSetChange {
+                    public_key: "n0".to_string(),
+                    old_threshold: 2,
+                    new_threshold: 3,
+                    added_validators: vec!["n3".to_string()],
+                    removed_validators: vec!["n1".to_string()],
+                },
+                QuorumSetChange {
+                    // n1 isn't part of the new FBAS and hence no longer a validator
+                    public_key: "n2".to_string(),
+                    old_threshold: 2,
+                    new_threshold: 2,
+                    added_validators: vec![],
+                    removed_validators: vec!["n1".to_string()],
+                },
+            ],
+        };
+        assert_eq!(expected, old_fbas.diff(&new_fbas));
+    }
+
+    #[test]
+    fn diff_detects_changes_in_inner_quorum_sets() {
+        let old_fbas = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": {
+                    "threshold": 1,
+                    "validators": [],
+                    "innerQuorumSets": [{ "threshold": 1, "validators": ["n0", "n1"] }]
+                }
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": { "threshold": 1, "validators": ["n1"] }
+            }
+        ]"#,
+        );
+        let mut new_fbas = old_fbas.clone();
+        let mut quorum_set = new_fbas.get_quorum_set(0).unwrap();
+        quorum_set.inner_quorum_sets[0].threshold = 2;
+        new_fbas.swap_quorum_set(0, quorum_set);
+
+        let diff = old_fbas.diff(&new_fbas);
+        assert_eq!(1, diff.changed_quorum_sets.len());
+        let change = &diff.changed_quorum_sets[0];
+        assert_eq!("n0", change.public_key);
+        assert_eq!(change.old_threshold, change.new_threshold);
+        assert!(change.added_validators.is_empty() && change.removed_validators.is_empty());
+    }
+
+    #[test]
+    fn groupings_diff_lists_membership_changes() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct_trivial.json"));
+        let old_orgs = Groupings::organizations_from_json_str(
+            r#"[
+            {
+                "id": "266107f8966d45eedce41fee2581326d",
+                "name": "Stellar Development Foundation",
+                "validators": [
+                    "GCGB2S2KGYARPVIA37HYZXVRM2YZUEXA6S33ZU5BUDC6THSB62LZSTYH",
+                    "GCM6QMP3DLRPTAZW2UZPCPX2LF3SXWXKPMP3GKFZBDSF3QZGV2G5QSTK"
+                ]
+            }]"#,
+            &fbas,
+        );
+        let new_orgs = Groupings::organizations_from_json_str(
+            r#"[
+            {
+                "id": "266107f8966d45eedce41fee2581326d",
+                "name": "Stellar Development Foundation",
+                "validators": [
+                    "GCGB2S2KGYARPVIA37HYZXVRM2YZUEXA6S33ZU5BUDC6THSB62LZSTYH",
+                    "GABMKJM6I25XI4K7U6XWMULOUQIQ27BCTMLS6BYYSOWKTBUXVRJSXHYQ"
+                ]
+            }]"#,
+            &fbas,
+        );
+        let expected = vec![
+            MembershipChange {
+                public_key: "GCM6QMP3DLRPTAZW2UZPCPX2LF3SXWXKPMP3GKFZBDSF3QZGV2G5QSTK".to_string(),
+                old_group: Some("Stellar Development Foundation".to_string()),
+                new_group: None,
+            },
+            MembershipChange {
+                public_key: "GABMKJM6I25XI4K7U6XWMULOUQIQ27BCTMLS6BYYSOWKTBUXVRJSXHYQ".to_string(),
+                old_group: None,
+                new_group: Some("Stellar Development Foundation".to_string()),
+            },
+        ];
+        assert_eq!(expected, old_orgs.diff(&new_orgs));
+    }
+}
@@ -97,6 +97,55 @@ impl Fbas {
         mem::swap(&mut self.nodes[node_id].quorum_set, &mut quorum_set);
         quorum_set
     }
+    /// Changes the public key of `node_id`. Quorum sets referencing the node are unaffected, as
+    /// they reference it by ID.
+    pub fn rename_node(&mut self, node_id: NodeId, public_key: PublicKey) {
+        if let Some(duplicate_id) = self.pk_to_id.get(&public_key) {
+            panic!(
+                "Duplicate public key {}",
+                self.nodes[*duplicate_id].public_key
+            );
+        }
+        let old_public_key = mem::replace(&mut self.nodes[node_id].public_key, public_key.clone());
+        self.pk_to_id.remove(&old_public_key);
+        self.pk_to_id.insert(public_key, node_id);
+    }
+    /// Removes `nodes` from the FBAS and from all quorum sets. If `reduce_thresholds` is set,
+    /// the threshold of each (inner) quorum set is reduced by the number of removed validators it
+    /// contained, as if the removed nodes had agreed (similar to deleting nodes as defined in the
+    /// SCP paper), but not below 1; inner quorum sets that only contained removed nodes are
+    /// dropped and count as removed validators. Otherwise, thresholds remain unchanged, as if the
+    /// removed nodes had crashed. The remaining nodes get new IDs (in the same order as before); use
+    /// `get_node_id` to look them up.
+    pub fn remove_nodes(&mut self, nodes: &NodeIdSet, reduce_thresholds: bool) {
+        if reduce_thresholds {
+            for node in self.nodes.iter_mut() {
+                node.quorum_set.reduce_thresholds(nodes);
+            }
+        }
+        let mut remaining_nodes = self.all_nodes();
+        remaining_nodes.difference_with(nodes);
+        *self = self.shrunken(remaining_nodes).0;
+    }
+    /// Replaces the quorum set of each node by `f(node_id, quorum_set)`.
+    pub fn map_quorum_sets<F>(&mut self, mut f: F)
+    where
+        F: FnMut(NodeId, QuorumSet) -> QuorumSet,
+    {
+        for (node_id, node) in self.nodes.iter_mut().enumerate() {
+            let quorum_set = mem::take(&mut node.quorum_set);
+            node.quorum_set = f(node_id, quorum_set);
+        }
+    }
+    /// Makes all quorum sets (including inner quorum sets) that reference `old` as validator
+    /// reference `new` instead. The node `old` itself remains part of the FBAS.
+    pub fn replace_validator(&mut self, old: NodeId, new: NodeId) {
+        assert!(new < self.nodes.len(), "Unknown node ID {}", new);
+        self.map_quorum_sets(|_, mut quorum_set| {
+            quorum_set.replace_validator(old, new);
+            quorum_set
+        });
+    }
     pub fn number_of_nodes(&self) -> usize {
         self.nodes.len()
     }
@@ -182,6 +231,136 @@ mod tests {
         fbas.add_node(node);
     }
 
+    fn three_node_fbas() -> Fbas {
+        Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1", "n2"] }
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": {
+                    "threshold": 2,
+                    "validators": ["n1"],
+                    "innerQuorumSets": [{ "threshold": 2, "validators": ["n0", "n2"] }]
+                }
+            },
+            {
+                "publicKey": "n2",
+                "quorumSet": { "threshold": 3, "validators": ["n0", "n1", "n2"] }
+            }
+        ]"#,
+        )
+    }
+
+    #[test]
+    fn remove_nodes_scrubs_quorum_sets_and_keeps_public_keys_consistent() {
+        let mut fbas = three_node_fbas();
+        fbas.remove_nodes(&bitset![0], false);
+        assert_eq!(2, fbas.number_of_nodes());
+        assert_eq!(None, fbas.get_node_id("n0"));
+        assert_eq!(Some(0), fbas.get_node_id("n1"));
+        assert_eq!(Some(1), fbas.get_node_id("n2"));
+        assert_eq!(
+            QuorumSet {
+                threshold: 2,
+                validators: vec![0],
+                inner_quorum_sets: vec![QuorumSet {
+                    threshold: 2,
+                    validators: vec![1],
+                    inner_quorum_sets: vec![],
+                }],
+            },
+            fbas.get_quorum_set(0).unwrap()
+        );
+        assert!(!fbas.is_quorum(&bitset![0, 1]));
+    }
+
+    #[test]
+    fn remove_nodes_can_reduce_thresholds() {
+        let mut fbas = three_node_fbas();
+        fbas.remove_nodes(&bitset![0], true);
+        assert_eq!(
+            QuorumSet {
+                threshold: 2,
+                validators: vec![0],
+                inner_quorum_sets: vec![QuorumSet {
+                    threshold: 1,
+                    validators: vec![1],
+                    inner_quorum_sets: vec![],
+                }],
+            },
+            fbas.get_quorum_set(0).unwrap()
+        );
+        assert_eq!(2, fbas.get_quorum_set(1).unwrap().threshold);
+        assert!(fbas.is_quorum(&bitset![0, 1]));
+
+        fbas.remove_nodes(&bitset![0, 1], true);
+        assert_eq!(0, fbas.number_of_nodes());
+    }
+
+    #[test]
+    fn remove_nodes_drops_emptied_inner_quorum_sets() {
+        let mut fbas = three_node_fbas();
+        fbas.remove_nodes(&bitset![0, 2], true);
+        assert_eq!(
+            QuorumSet {
+                threshold: 1,
+                validators: vec![0],
+                inner_quorum_sets: vec![],
+            },
+            fbas.get_quorum_set(0).unwrap()
+        );
+        assert!(fbas.is_quorum(&bitset![0]));
+    }
+
+    #[test]
+    fn map_quorum_sets_modifies_all_quorum_sets() {
+        let mut fbas = three_node_fbas();
+        fbas.map_quorum_sets(|node_id, quorum_set| {
+            if node_id == 2 {
+                QuorumSet {
+                    threshold: 2,
+                    ..quorum_set
+                }
+            } else {
+                quorum_set
+            }
+        });
+        assert_eq!(2, fbas.get_quorum_set(2).unwrap().threshold);
+        assert_eq!(three_node_fbas().get_quorum_set(0), fbas.get_quorum_set(0));
+    }
+
+    #[test]
+    fn replace_validator_rewrites_references() {
+        let mut fbas = three_node_fbas();
+        fbas.add_node(Node::new("n3".to_string()));
+        fbas.replace_validator(0, 3);
+        assert_eq!(vec![3, 1, 2], fbas.get_quorum_set(0).unwrap().validators);
+        assert_eq!(
+            vec![3, 2],
+            fbas.get_quorum_set(1).unwrap().inner_quorum_sets[0].validators
+        );
+        assert_eq!(4, fbas.number_of_nodes());
+    }
+
+    #[test]
+    fn rename_node_keeps_public_keys_consistent() {
+        let mut fbas = three_node_fbas();
+        fbas.rename_node(1, "renamed".to_string());
+        assert_eq!(None, fbas.get_node_id("n1"));
+        assert_eq!(Some(1), fbas.get_node_id("renamed"));
+        assert_eq!(three_node_fbas().get_quorum_set(0), fbas.get_quorum_set(0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn rename_node_panics_on_duplicate_public_key() {
+        let mut fbas = three_node_fbas();
+        fbas.rename_node(1, "n2".to_string());
+    }
+
     #[test]
     fn is_quorum_for_fbas() {
         let fbas = Fbas::from_json_file(Path::new("test_data/correct_trivial.json"));
@@ -51,3 +51,8 @@ impl<'fbas> Groupings<'fbas> {
         self.groupings.len()
     }
 }
+impl Grouping {
+    pub fn validators(&self) -> &[NodeId] {
+        &self.validators
+    }
+}
@@ -15,12 +15,16 @@ pub type PublicKey = String;
 pub type NodeIdSet = BitSet;
 pub type NodeIdDeque = VecDeque<NodeId>;
 
+mod builder;
+mod diff;
 mod fbas;
 mod groupings;
 mod quorum_set;
 mod set_helpers;
 mod shrinking;
 
+pub use builder::*;
+pub use diff::*;
 pub use fbas::*;
 pub use groupings::*;
 pub use quorum_set::*;
@@ -1,5 +1,6 @@
 use super::*;
 use itertools::Itertools;
+use std::cmp::max;
 
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -27,6 +28,17 @@ impl QuorumSet {
         let nodes_set: NodeIdSet = nodes_vec.iter().copied().collect();
         nodes_vec.len() != nodes_set.len()
     }
+    /// Nodes that appear more than once
+    pub(crate) fn duplicate_nodes(&self) -> NodeIdSet {
+        let mut seen = bitset![];
+        let mut duplicates = bitset![];
+        for node_id in self.contained_nodes_with_duplicates() {
+            if !seen.insert(node_id) {
+                duplicates.insert(node_id);
+            }
+        }
+        duplicates
+    }
     pub fn is_quorum_slice(&self, node_set: &NodeIdSet) -> bool {
         if self.threshold == 0 {
             false // badly configured quorum set
@@ -80,6 +92,122 @@ impl QuorumSet {
             })
             .concat()
     }
+    /// Returns an equivalent quorum set (one with the same quorum slices) in a canonical form:
+    /// inner quorum sets with only one entry are replaced by this entry, nested quorum sets that
+    /// require all (or any one) of their entries are merged into parents that do the same,
+    /// duplicate entries are removed where this doesn't change the quorum slices, and validators
+    /// and inner quorum sets are sorted. Quorum sets that can't be satisfied become
+    /// `QuorumSet::new()`.
+    pub fn normalized(&self) -> Self {
+        let mut validators = self.validators.clone();
+        let mut inner_quorum_sets = vec![];
+        let mut threshold = self.threshold;
+        for inner_quorum_set in self.inner_quorum_sets.iter().map(|q| q.normalized()) {
+            let inner_entries =
+                inner_quorum_set.validators.len() + inner_quorum_set.inner_quorum_sets.len();
+            if inner_quorum_set.threshold == 0 {
+                // can never be satisfied; counts towards no threshold
+            } else if inner_entries == 1 {
+                validators.extend(inner_quorum_set.validators);
+                inner_quorum_sets.extend(inner_quorum_set.inner_quorum_sets);
+            } else {
+                inner_quorum_sets.push(inner_quorum_set);
+            }
+        }
+        let entries = validators.len() + inner_quorum_sets.len();
+        if threshold == 0 || threshold > entries {
+            return QuorumSet::new();
+        }
+        // merge children that require all of their entries into parents that do the same, and
+        // children that require any one of their entries into parents that do the same
+        if threshold == entries || threshold == 1 {
+            let (mergeable, other): (Vec<QuorumSet>, Vec<QuorumSet>) =
+                inner_quorum_sets.into_iter().partition(|inner_quorum_set| {
+                    let inner_entries = inner_quorum_set.validators.len()
+                        + inner_quorum_set.inner_quorum_sets.len();
+                    (threshold == 1) == (inner_quorum_set.threshold == 1)
+                        && (threshold == 1 || inner_quorum_set.threshold == inner_entries)
+                });
+            inner_quorum_sets = other;
+            for inner_quorum_set in mergeable.into_iter() {
+                if threshold > 1 {
+                    threshold += inner_quorum_set.threshold - 1;
+                }
+                validators.extend(inner_quorum_set.validators);
+                inner_quorum_sets.extend(inner_quorum_set.inner_quorum_sets);
+            }
+        }
+        validators.sort_unstable();
+        inner_quorum_sets.sort_unstable();
+        // with "all of" or "any one of" semantics, each entry needs to be listed only once
+        let entries = validators.len() + inner_quorum_sets.len();
+        if threshold == entries || threshold == 1 {
+            validators.dedup();
+            inner_quorum_sets.dedup();
+            if threshold > 1 {
+                threshold = validators.len() + inner_quorum_sets.len();
+            }
+        }
+        if threshold == 1 && validators.is_empty() && inner_quorum_sets.len() == 1 {
+            inner_quorum_sets.pop().unwrap()
+        } else {
+            QuorumSet {
+                threshold,
+                validators,
+                inner_quorum_sets,
+            }
+        }
+    }
+    /// Whether `self` and `other` have the same quorum slices, i.e., whether each set of nodes is
+    /// a quorum slice for `self` if and only if it is one for `other`. Compares normalized forms
+    /// (see `normalized`) first and falls back to comparing minimal quorum slices, which can be
+    /// expensive for large, deeply nested quorum sets.
+    pub fn is_equivalent(&self, other: &Self) -> bool {
+        let (normalized_self, normalized_other) = (self.normalized(), other.normalized());
+        if normalized_self == normalized_other {
+            true
+        } else if normalized_self.threshold == 0 || normalized_other.threshold == 0 {
+            false // only one of them can't be satisfied
+        } else {
+            remove_non_minimal_node_sets(normalized_self.to_quorum_slices())
+                == remove_non_minimal_node_sets(normalized_other.to_quorum_slices())
+        }
+    }
+    /// Inner quorum sets that only contain removed nodes are dropped and count as removed
+    /// validators themselves.
+    pub(crate) fn reduce_thresholds(&mut self, removed_nodes: &NodeIdSet) {
+        let removed_validators = self
+            .validators
+            .iter()
+            .filter(|&&validator| removed_nodes.contains(validator))
+            .count();
+        let number_of_inner_quorum_sets = self.inner_quorum_sets.len();
+        self.inner_quorum_sets.retain(|inner_quorum_set| {
+            let contained_nodes = inner_quorum_set.contained_nodes();
+            contained_nodes.is_empty() || !contained_nodes.is_subset(removed_nodes)
+        });
+        let removed_inner_quorum_sets = number_of_inner_quorum_sets - self.inner_quorum_sets.len();
+        if self.threshold > 0 {
+            self.threshold = max(
+                self.threshold
+                    .saturating_sub(removed_validators + removed_inner_quorum_sets),
+                1,
+            );
+        }
+        for inner_quorum_set in self.inner_quorum_sets.iter_mut() {
+            inner_quorum_set.reduce_thresholds(removed_nodes);
+        }
+    }
+    pub(crate) fn replace_validator(&mut self, old: NodeId, new: NodeId) {
+        for validator in self.validators.iter_mut() {
+            if *validator == old {
+                *validator = new;
+            }
+        }
+        for inner_quorum_set in self.inner_quorum_sets.iter_mut() {
+            inner_quorum_set.replace_validator(old, new);
+        }
+    }
     fn contained_nodes_with_duplicates(&self) -> Vec<NodeId> {
         let mut nodes = self.validators.clone();
         for inner_quorum_set in self.inner_quorum_sets.iter() {
@@ -194,6 +322,98 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn normalized_flattens_trivially_nested_quorum_sets() {
+        let quorum_set = QuorumSet {
+            threshold: 2,
+            validators: vec![2],
+            inner_quorum_sets: vec![
+                flat_qset(&[1], 1),
+                QuorumSet {
+                    threshold: 1,
+                    validators: vec![],
+                    inner_quorum_sets: vec![flat_qset(&[0], 1)],
+                },
+            ],
+        };
+        let expected = flat_qset(&[0, 1, 2], 2);
+        assert_eq!(expected, quorum_set.normalized());
+    }
+
+    #[test]
+    fn normalized_merges_nested_all_of_and_any_of_quorum_sets() {
+        let all_of = QuorumSet {
+            threshold: 2,
+            validators: vec![0],
+            inner_quorum_sets: vec![flat_qset(&[2, 1], 2)],
+        };
+        assert_eq!(flat_qset(&[0, 1, 2], 3), all_of.normalized());
+
+        let any_of = QuorumSet {
+            threshold: 1,
+            validators: vec![3],
+            inner_quorum_sets: vec![flat_qset(&[2, 1], 1)],
+        };
+        assert_eq!(flat_qset(&[1, 2, 3], 1), any_of.normalized());
+    }
+
+    #[test]
+    fn normalized_removes_only_redundant_duplicates() {
+        assert_eq!(flat_qset(&[0, 1], 2), flat_qset(&[1, 0, 1], 3).normalized());
+        assert_eq!(flat_qset(&[0, 1], 1), flat_qset(&[1, 0, 1], 1).normalized());
+        // {n1} is a quorum slice here, so the duplicate must stay
+        assert_eq!(
+            flat_qset(&[0, 1, 1], 2),
+            flat_qset(&[1, 0, 1], 2).normalized()
+        );
+    }
+
+    #[test]
+    fn normalized_handles_unsatisfiable_quorum_sets() {
+        assert_eq!(QuorumSet::new(), flat_qset(&[0, 1], 0).normalized());
+        assert_eq!(QuorumSet::new(), flat_qset(&[0, 1], 3).normalized());
+        let quorum_set = QuorumSet {
+            threshold: 1,
+            validators: vec![0],
+            inner_quorum_sets: vec![flat_qset(&[1, 2], 3)],
+        };
+        assert_eq!(flat_qset(&[0], 1), quorum_set.normalized());
+    }
+
+    #[test]
+    fn normalized_is_equivalent() {
+        let quorum_set = QuorumSet {
+            threshold: 3,
+            validators: vec![1, 0],
+            inner_quorum_sets: vec![
+                flat_qset(&[3, 2], 1),
+                QuorumSet {
+                    threshold: 3,
+                    validators: vec![4, 3],
+                    inner_quorum_sets: vec![flat_qset(&[5], 1)],
+                },
+            ],
+        };
+        let normalized = quorum_set.normalized();
+        assert_ne!(quorum_set, normalized);
+        assert!(quorum_set.is_equivalent(&normalized));
+        assert_eq!(normalized, normalized.normalized());
+    }
+
+    #[test]
+    fn is_equivalent_compares_quorum_slices() {
+        // 2 out of {n0, 2 out of {n0, n1}} has the same slices as 2 out of {n0, n1}
+        let quorum_set = QuorumSet {
+            threshold: 2,
+            validators: vec![0],
+            inner_quorum_sets: vec![flat_qset(&[0, 1], 2)],
+        };
+        assert!(quorum_set.is_equivalent(&flat_qset(&[0, 1], 2)));
+        assert!(!quorum_set.is_equivalent(&flat_qset(&[0, 1], 1)));
+        assert!(!quorum_set.is_equivalent(&QuorumSet::new()));
+        assert!(flat_qset(&[0], 2).is_equivalent(&QuorumSet::new()));
+    }
+
     #[test]
     fn duplicate_validators() {
         let quorum_set = QuorumSet {
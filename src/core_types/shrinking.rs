@@ -90,11 +90,9 @@ impl Fbas {
         let unshrink_table = &shrink_manager.unshrink_table;
         let shrink_map = &shrink_manager.shrink_map;
 
-        let mut fbas_shrunken = Fbas::new_generic_unconfigured(unshrink_table.len());
-        for old_id in 0..self.nodes.len() {
-            if let Some(&new_id) = shrink_map.get(&old_id) {
-                fbas_shrunken.nodes[new_id] = Node::shrunken(&self.nodes[old_id], &shrink_map);
-            }
+        let mut fbas_shrunken = Fbas::new();
+        for &old_id in unshrink_table.iter() {
+            fbas_shrunken.add_node(Node::shrunken(&self.nodes[old_id], &shrink_map));
         }
         (fbas_shrunken, shrink_manager)
     }
@@ -0,0 +1,106 @@
+//! On-disk format of the results cached by an `Analysis` (see `Analysis::save_cache`).
+
+use super::*;
+
+use sha3::{Digest, Sha3_256};
+
+/// Results are stored in terms of the shrunken node IDs used internally by `Analysis`;
+/// `shrunken_public_keys` maps these IDs to public keys, so that the results can be reused for
+/// FBASs that have the same standard form but a different node order.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RawAnalysisCache {
+    /// See `Fbas::standard_form_hash`.
+    pub(crate) fbas_hash: String,
+    pub(crate) shrunken_public_keys: Vec<PublicKey>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) has_quorum_intersection: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) minimal_quorums: Option<Vec<Vec<NodeId>>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) minimal_blocking_sets: Option<Vec<Vec<NodeId>>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) minimal_splitting_sets: Option<Vec<Vec<NodeId>>>,
+}
+impl RawAnalysisCache {
+    pub(crate) fn from_json_file(path: &Path) -> Result<Self, Error> {
+        parse_json(&read_file(path)?)
+    }
+    pub(crate) fn to_json_file(&self, path: &Path) -> Result<(), Error> {
+        let json = serde_json::to_string(self).expect("Error converting analysis cache to JSON!");
+        fs::write(path, json).map_err(|source| Error::Io {
+            path: Some(path.to_path_buf()),
+            source,
+        })
+    }
+}
+
+impl Fbas {
+    /// Hash of the FBAS in standard form (see `Fbas::to_standard_form`), as a hex string. FBASs
+    /// with the same standard form hash yield the same analysis results (modulo node IDs). The
+    /// hash is the SHA3-256 hash of the standard form's JSON representation, as also reported by
+    /// `bulk_fbas_analyzer`.
+    pub fn standard_form_hash(&self) -> String {
+        hex::encode(Sha3_256::digest(
+            &self.to_standard_form().to_json_string().into_bytes(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn standard_form_hash_ignores_node_order_and_irrelevant_nodes() {
+        let fbas = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1"] }
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1"] }
+            }
+        ]"#,
+        );
+        let reordered_fbas_with_irrelevant_node = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "n2",
+                "quorumSet": { "threshold": 1, "validators": ["n0"] }
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1"] }
+            },
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1"] }
+            }
+        ]"#,
+        );
+        let different_fbas = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 1, "validators": ["n0", "n1"] }
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1"] }
+            }
+        ]"#,
+        );
+        assert_eq!(64, fbas.standard_form_hash().len());
+        assert_eq!(
+            fbas.standard_form_hash(),
+            reordered_fbas_with_irrelevant_node.standard_form_hash()
+        );
+        assert_ne!(
+            fbas.standard_form_hash(),
+            different_fbas.standard_form_hash()
+        );
+    }
+}
@@ -28,15 +28,39 @@ pub(crate) struct RawGeoData {
     pub(crate) country_name: Option<String>,
 }
 
+impl RawQuorumSet {
+    /// All public keys referenced in this quorum set, including duplicates.
+    pub(crate) fn contained_public_keys(&self) -> Vec<PublicKey> {
+        let mut public_keys = self.validators.clone();
+        for inner_quorum_set in self.inner_quorum_sets.iter() {
+            public_keys.append(&mut inner_quorum_set.contained_public_keys());
+        }
+        public_keys
+    }
+}
+
 impl Fbas {
     pub fn from_json_str(json: &str) -> Self {
-        serde_json::from_str(json).expect("Error parsing FBAS JSON")
+        Self::try_from_json_str(json).unwrap_or_else(|e| panic!("Error parsing FBAS JSON: {}", e))
     }
     pub fn from_json_file(path: &Path) -> Self {
-        Self::from_json_str(&read_or_panic!(path))
+        Self::try_from_json_file(path).unwrap_or_else(|e| panic!("Error parsing FBAS JSON: {}", e))
     }
     pub fn from_json_stdin() -> Self {
-        serde_json::from_reader(io::stdin()).expect("Error reading FBAS JSON from STDIN")
+        Self::try_from_json_stdin()
+            .unwrap_or_else(|e| panic!("Error reading FBAS JSON from STDIN: {}", e))
+    }
+    /// Like `from_json_str`, but returns an `Error` instead of panicking on bad input.
+    pub fn try_from_json_str(json: &str) -> Result<Self, Error> {
+        Self::try_from_raw(parse_json(json)?)
+    }
+    /// Like `from_json_file`, but returns an `Error` instead of panicking on bad input.
+    pub fn try_from_json_file(path: &Path) -> Result<Self, Error> {
+        Self::try_from_json_str(&read_file(path)?)
+    }
+    /// Like `from_json_stdin`, but returns an `Error` instead of panicking on bad input.
+    pub fn try_from_json_stdin() -> Result<Self, Error> {
+        Self::try_from_json_str(&read_stdin()?)
     }
     pub fn to_json_string(&self) -> String {
         serde_json::to_string(&self).expect("Error converting FBAS to JSON!")
@@ -60,6 +84,21 @@ impl Fbas {
 
         Fbas { nodes, pk_to_id }
     }
+    pub(crate) fn try_from_raw(raw_fbas: RawFbas) -> Result<Self, Error> {
+        let mut pk_to_id: HashMap<PublicKey, NodeId> = HashMap::with_capacity(raw_fbas.0.len());
+        for (node_id, raw_node) in raw_fbas.0.iter().enumerate() {
+            if pk_to_id
+                .insert(raw_node.public_key.clone(), node_id)
+                .is_some()
+            {
+                return Err(Error::DuplicatePublicKey {
+                    json_path: format!("[{}].publicKey", node_id),
+                    public_key: raw_node.public_key.clone(),
+                });
+            }
+        }
+        Ok(Self::from_raw(raw_fbas))
+    }
     pub(crate) fn to_raw(&self) -> RawFbas {
         RawFbas(self.nodes.iter().map(|n| n.to_raw(&self)).collect())
     }
@@ -83,7 +122,7 @@ impl<'de> Deserialize<'de> for Fbas {
         D: Deserializer<'de>,
     {
         let raw_fbas = RawFbas::deserialize(deserializer)?;
-        Ok(Fbas::from_raw(raw_fbas))
+        Fbas::try_from_raw(raw_fbas).map_err(serde::de::Error::custom)
     }
 }
 impl Node {
@@ -103,7 +142,10 @@ impl Node {
     }
 }
 impl QuorumSet {
-    fn from_raw(raw_quorum_set: RawQuorumSet, pk_to_id: &HashMap<PublicKey, NodeId>) -> Self {
+    pub(crate) fn from_raw(
+        raw_quorum_set: RawQuorumSet,
+        pk_to_id: &HashMap<PublicKey, NodeId>,
+    ) -> Self {
         let mut validators: Vec<NodeId> = raw_quorum_set
             .validators
             .into_iter()
@@ -279,6 +321,87 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn try_from_json_str_reports_schema_mismatch_with_path_and_public_key() {
+        let input = r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 1, "validators": ["n1"] }
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": {
+                    "threshold": 1,
+                    "validators": [],
+                    "innerQuorumSets": [{ "threshold": "one", "validators": ["n0"] }]
+                }
+            }]"#;
+        match Fbas::try_from_json_str(input) {
+            Err(Error::SchemaMismatch {
+                json_path,
+                public_key,
+                ..
+            }) => {
+                assert_eq!("[1].quorumSet.innerQuorumSets[0].threshold", json_path);
+                assert_eq!(Some(String::from("n1")), public_key);
+            }
+            other => panic!("Expected schema mismatch, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn try_from_json_str_reports_missing_public_key() {
+        let input =
+            r#"[{ "publicKey": "n0" }, { "quorumSet": { "threshold": 0, "validators": [] } }]"#;
+        match Fbas::try_from_json_str(input) {
+            Err(Error::SchemaMismatch {
+                json_path,
+                public_key,
+                ..
+            }) => {
+                assert_eq!("[1]", json_path);
+                assert_eq!(None, public_key);
+            }
+            other => panic!("Expected schema mismatch, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn try_from_json_str_reports_syntax_errors() {
+        let input = r#"[{ "publicKey": "n0" },"#;
+        assert!(matches!(
+            Fbas::try_from_json_str(input),
+            Err(Error::JsonSyntax(_))
+        ));
+    }
+
+    #[test]
+    fn try_from_json_str_reports_duplicate_public_keys() {
+        let input = r#"[{ "publicKey": "n0" }, { "publicKey": "n1" }, { "publicKey": "n0" }]"#;
+        match Fbas::try_from_json_str(input) {
+            Err(Error::DuplicatePublicKey {
+                json_path,
+                public_key,
+            }) => {
+                assert_eq!("[2].publicKey", json_path);
+                assert_eq!("n0", public_key);
+            }
+            other => panic!("Expected duplicate public key error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn try_from_json_file_reports_io_errors() {
+        let actual = Fbas::try_from_json_file(Path::new("test_data/there_is_no_such_file.json"));
+        assert!(matches!(actual, Err(Error::Io { .. })));
+    }
+
+    #[test]
+    #[should_panic]
+    fn from_json_str_panics_on_duplicate_public_keys() {
+        Fbas::from_json_str(r#"[{ "publicKey": "n0" }, { "publicKey": "n0" }]"#);
+    }
+
     #[test]
     fn to_json_and_back_results_in_identical_fbas() {
         let original = Fbas::new_generic_unconfigured(7);
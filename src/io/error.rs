@@ -0,0 +1,185 @@
+use super::*;
+use std::error;
+use std::path::PathBuf;
+
+use serde::de::DeserializeOwned;
+
+/// Errors that can occur while reading FBAS and grouping data.
+#[derive(Debug)]
+pub enum Error {
+    /// Reading from (or writing to) a file or STDIN failed. `path` is `None` for STDIN.
+    Io {
+        path: Option<PathBuf>,
+        source: io::Error,
+    },
+    /// The input is not syntactically valid JSON.
+    JsonSyntax(serde_json::Error),
+    /// The input is valid JSON but doesn't have the expected structure.
+    SchemaMismatch {
+        /// Location of the offending value, e.g., `[3].quorumSet.threshold`.
+        json_path: String,
+        /// Public key of the node the offending value belongs to, if it could be determined.
+        public_key: Option<PublicKey>,
+        message: String,
+    },
+    /// More than one node uses the same public key.
+    DuplicatePublicKey {
+        /// Location of the duplicate, e.g., `[42].publicKey`.
+        json_path: String,
+        public_key: PublicKey,
+    },
+    /// The input is not syntactically valid TOML.
+    TomlSyntax(toml::de::Error),
+    /// The stellar-core configuration is valid TOML but doesn't describe a valid quorum set.
+    InvalidStellarCoreConfig(String),
+    /// A cache file (see `Analysis::save_cache`) is valid JSON but its contents don't fit the
+    /// analyzed FBAS.
+    InvalidAnalysisCache(String),
+}
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io {
+                path: Some(path),
+                source,
+            } => write!(f, "error accessing file {:?}: {}", path, source),
+            Error::Io { path: None, source } => write!(f, "error reading STDIN: {}", source),
+            Error::JsonSyntax(source) => write!(f, "invalid JSON: {}", source),
+            Error::SchemaMismatch {
+                json_path,
+                public_key: Some(public_key),
+                message,
+            } => write!(
+                f,
+                "unexpected data at {} (node {}): {}",
+                json_path, public_key, message
+            ),
+            Error::SchemaMismatch {
+                json_path,
+                public_key: None,
+                message,
+            } => write!(f, "unexpected data at {}: {}", json_path, message),
+            Error::DuplicatePublicKey {
+                json_path,
+                public_key,
+            } => write!(f, "duplicate public key {} at {}", public_key, json_path),
+            Error::TomlSyntax(source) => write!(f, "invalid TOML: {}", source),
+            Error::InvalidStellarCoreConfig(message) => {
+                write!(f, "invalid stellar-core configuration: {}", message)
+            }
+            Error::InvalidAnalysisCache(message) => {
+                write!(f, "invalid analysis cache: {}", message)
+            }
+        }
+    }
+}
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            Error::JsonSyntax(source) => Some(source),
+            Error::TomlSyntax(source) => Some(source),
+            _ => None,
+        }
+    }
+}
+
+pub(crate) fn read_file(path: &Path) -> Result<String, Error> {
+    fs::read_to_string(path).map_err(|source| Error::Io {
+        path: Some(path.to_path_buf()),
+        source,
+    })
+}
+
+pub(crate) fn read_stdin() -> Result<String, Error> {
+    let mut buffer = String::new();
+    io::Read::read_to_string(&mut io::stdin(), &mut buffer)
+        .map_err(|source| Error::Io { path: None, source })?;
+    Ok(buffer)
+}
+
+/// Like `serde_json::from_str`, but keeps track of where in the JSON document things went wrong.
+pub(crate) fn parse_json<T: DeserializeOwned>(json: &str) -> Result<T, Error> {
+    let mut deserializer = serde_json::Deserializer::from_str(json);
+    let result = serde_path_to_error::deserialize(&mut deserializer)
+        .map_err(|e| Error::from_path_error(e, json))?;
+    deserializer.end().map_err(Error::JsonSyntax)?;
+    Ok(result)
+}
+
+impl Error {
+    fn from_path_error(
+        path_error: serde_path_to_error::Error<serde_json::Error>,
+        json: &str,
+    ) -> Self {
+        let json_path = path_error.path().to_string();
+        let top_level_index = match path_error.path().iter().next() {
+            Some(serde_path_to_error::Segment::Seq { index }) => Some(*index),
+            _ => None,
+        };
+        let source = path_error.into_inner();
+        match source.classify() {
+            serde_json::error::Category::Data => Error::SchemaMismatch {
+                json_path,
+                public_key: top_level_index.and_then(|i| find_public_key_of_json_node(json, i)),
+                message: source.to_string(),
+            },
+            _ => Error::JsonSyntax(source),
+        }
+    }
+}
+
+/// Looks up the `publicKey` of the `index`-th entry of a JSON list of nodes, if there is one.
+fn find_public_key_of_json_node(json: &str, index: usize) -> Option<PublicKey> {
+    let value: serde_json::Value = serde_json::from_str(json).ok()?;
+    value
+        .get(index)?
+        .get("publicKey")?
+        .as_str()
+        .map(PublicKey::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_json_reports_syntax_errors() {
+        let actual = parse_json::<Vec<u64>>("[1, 2,");
+        assert!(matches!(actual, Err(Error::JsonSyntax(_))));
+    }
+
+    #[test]
+    fn parse_json_reports_trailing_characters_as_syntax_errors() {
+        let actual = parse_json::<Vec<u64>>("[1, 2] 3");
+        assert!(matches!(actual, Err(Error::JsonSyntax(_))));
+    }
+
+    #[test]
+    fn parse_json_reports_path_of_schema_mismatch() {
+        let actual = parse_json::<Vec<Vec<u64>>>(r#"[[1], [2, "three"]]"#);
+        match actual {
+            Err(Error::SchemaMismatch {
+                json_path,
+                public_key,
+                ..
+            }) => {
+                assert_eq!("[1][1]", json_path);
+                assert_eq!(None, public_key);
+            }
+            other => panic!("Expected schema mismatch, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn read_file_reports_path_of_missing_file() {
+        let path = Path::new("test_data/there_is_no_such_file.json");
+        match read_file(path) {
+            Err(Error::Io {
+                path: Some(actual_path),
+                ..
+            }) => assert_eq!(path, actual_path),
+            other => panic!("Expected I/O error, got {:?}", other),
+        }
+    }
+}
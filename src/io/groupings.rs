@@ -9,40 +9,77 @@ struct RawGrouping {
 }
 impl<'fbas> Groupings<'fbas> {
     pub fn from_json_str(json: &str, fbas: &'fbas Fbas) -> Self {
-        Self::from_raw(
-            serde_json::from_str(json).expect("Error parsing Groupings JSON"),
-            fbas,
-        )
+        Self::try_from_json_str(json, fbas)
+            .unwrap_or_else(|e| panic!("Error parsing Groupings JSON: {}", e))
     }
     pub fn organizations_from_json_str(orgs_json: &str, fbas: &'fbas Fbas) -> Self {
-        Self::from_raw(
-            serde_json::from_str(orgs_json).expect("Error parsing Organizations JSON"),
-            fbas,
-        )
+        Self::try_organizations_from_json_str(orgs_json, fbas)
+            .unwrap_or_else(|e| panic!("Error parsing Organizations JSON: {}", e))
     }
     pub fn isps_from_json_str(nodes_json: &str, fbas: &'fbas Fbas) -> Self {
-        let raw_nodes: Vec<RawNode> =
-            serde_json::from_str(&nodes_json).expect("Error parsing FBAS JSON");
-        let raw_groupings = RawGroupings::isps_from_raw_nodes(raw_nodes);
-        Groupings::from_raw(raw_groupings, &fbas)
+        Self::try_isps_from_json_str(nodes_json, fbas)
+            .unwrap_or_else(|e| panic!("Error parsing FBAS JSON: {}", e))
     }
     pub fn countries_from_json_str(nodes_json: &str, fbas: &'fbas Fbas) -> Self {
-        let raw_nodes: Vec<RawNode> =
-            serde_json::from_str(&nodes_json).expect("Error parsing FBAS JSON");
-        let raw_groupings = RawGroupings::countries_from_raw_nodes(raw_nodes);
-        Groupings::from_raw(raw_groupings, &fbas)
+        Self::try_countries_from_json_str(nodes_json, fbas)
+            .unwrap_or_else(|e| panic!("Error parsing FBAS JSON: {}", e))
     }
     pub fn from_json_file(path: &Path, fbas: &'fbas Fbas) -> Self {
-        Self::from_json_str(&read_or_panic!(path), fbas)
+        Self::try_from_json_file(path, fbas)
+            .unwrap_or_else(|e| panic!("Error parsing Groupings JSON: {}", e))
     }
     pub fn organizations_from_json_file(path: &Path, fbas: &'fbas Fbas) -> Self {
-        Self::organizations_from_json_str(&read_or_panic!(path), fbas)
+        Self::try_organizations_from_json_file(path, fbas)
+            .unwrap_or_else(|e| panic!("Error parsing Organizations JSON: {}", e))
     }
     pub fn isps_from_json_file(path: &Path, fbas: &'fbas Fbas) -> Self {
-        Self::isps_from_json_str(&read_or_panic!(path), &fbas)
+        Self::try_isps_from_json_file(path, fbas)
+            .unwrap_or_else(|e| panic!("Error parsing FBAS JSON: {}", e))
     }
     pub fn countries_from_json_file(path: &Path, fbas: &'fbas Fbas) -> Self {
-        Self::countries_from_json_str(&read_or_panic!(path), &fbas)
+        Self::try_countries_from_json_file(path, fbas)
+            .unwrap_or_else(|e| panic!("Error parsing FBAS JSON: {}", e))
+    }
+    /// Like `from_json_str`, but returns an `Error` instead of panicking on bad input.
+    pub fn try_from_json_str(json: &str, fbas: &'fbas Fbas) -> Result<Self, Error> {
+        Ok(Self::from_raw(parse_json(json)?, fbas))
+    }
+    /// Like `organizations_from_json_str`, but returns an `Error` instead of panicking on bad
+    /// input.
+    pub fn try_organizations_from_json_str(
+        orgs_json: &str,
+        fbas: &'fbas Fbas,
+    ) -> Result<Self, Error> {
+        Self::try_from_json_str(orgs_json, fbas)
+    }
+    /// Like `isps_from_json_str`, but returns an `Error` instead of panicking on bad input.
+    pub fn try_isps_from_json_str(nodes_json: &str, fbas: &'fbas Fbas) -> Result<Self, Error> {
+        let raw_nodes: Vec<RawNode> = parse_json(nodes_json)?;
+        let raw_groupings = RawGroupings::isps_from_raw_nodes(raw_nodes);
+        Ok(Groupings::from_raw(raw_groupings, &fbas))
+    }
+    /// Like `countries_from_json_str`, but returns an `Error` instead of panicking on bad input.
+    pub fn try_countries_from_json_str(nodes_json: &str, fbas: &'fbas Fbas) -> Result<Self, Error> {
+        let raw_nodes: Vec<RawNode> = parse_json(nodes_json)?;
+        let raw_groupings = RawGroupings::countries_from_raw_nodes(raw_nodes);
+        Ok(Groupings::from_raw(raw_groupings, &fbas))
+    }
+    /// Like `from_json_file`, but returns an `Error` instead of panicking on bad input.
+    pub fn try_from_json_file(path: &Path, fbas: &'fbas Fbas) -> Result<Self, Error> {
+        Self::try_from_json_str(&read_file(path)?, fbas)
+    }
+    /// Like `organizations_from_json_file`, but returns an `Error` instead of panicking on bad
+    /// input.
+    pub fn try_organizations_from_json_file(path: &Path, fbas: &'fbas Fbas) -> Result<Self, Error> {
+        Self::try_organizations_from_json_str(&read_file(path)?, fbas)
+    }
+    /// Like `isps_from_json_file`, but returns an `Error` instead of panicking on bad input.
+    pub fn try_isps_from_json_file(path: &Path, fbas: &'fbas Fbas) -> Result<Self, Error> {
+        Self::try_isps_from_json_str(&read_file(path)?, fbas)
+    }
+    /// Like `countries_from_json_file`, but returns an `Error` instead of panicking on bad input.
+    pub fn try_countries_from_json_file(path: &Path, fbas: &'fbas Fbas) -> Result<Self, Error> {
+        Self::try_countries_from_json_str(&read_file(path)?, fbas)
     }
     fn from_raw(raw_groupings: RawGroupings, fbas: &'fbas Fbas) -> Self {
         let groupings: Vec<Grouping> = raw_groupings
@@ -213,6 +250,23 @@ mod tests {
         assert_eq!(expected_validators, actual_validators);
     }
     #[test]
+    fn try_organizations_from_json_str_reports_schema_mismatch_with_path() {
+        let fbas = Fbas::new_generic_unconfigured(2);
+        let json = r#"[
+            {
+                "name": "Good Org",
+                "validators": [ "n0" ]
+            },
+            {
+                "name": "Bad Org",
+                "validators": "n1"
+            }]"#;
+        match Groupings::try_organizations_from_json_str(json, &fbas) {
+            Err(Error::SchemaMismatch { json_path, .. }) => assert_eq!("[1].validators", json_path),
+            other => panic!("Expected schema mismatch, got {:?}", other),
+        }
+    }
+    #[test]
     fn read_countries_from_nodes_json_str() {
         let json = r#"[
             {
@@ -5,19 +5,27 @@ use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::*;
 
-macro_rules! read_or_panic {
-    ($path:expr) => {{
-        fs::read_to_string($path).unwrap_or_else(|_| panic!("Error reading file {:?}", $path))
-    }};
-}
+mod error;
+pub use error::Error;
+use error::*;
 
 mod core_types;
 use core_types::*;
 
+mod analysis_cache;
+pub(crate) use analysis_cache::RawAnalysisCache;
+
 mod groupings;
+mod stellar_core;
+mod stellar_core_config;
+mod trust_graph;
+pub use trust_graph::TrustGraphAnnotations;
 
 mod results;
 pub use results::*;
 
+mod validation;
+pub use validation::*;
+
 #[cfg(feature = "qsc-simulation")]
 mod graph;
@@ -27,6 +27,10 @@ pub trait AnalysisResult: Sized + Serialize {
         self.into_id_string()
     }
     fn into_describe_string(self) -> String;
+    /// Whether the result is incomplete because its computation got cancelled.
+    fn is_partial(&self) -> bool {
+        false
+    }
 }
 
 // semantically strange, but for convenience
@@ -87,6 +91,9 @@ impl AnalysisResult for NodeIdSetResult {
     fn into_describe_string(self) -> String {
         self.len().to_string()
     }
+    fn is_partial(&self) -> bool {
+        NodeIdSetResult::is_partial(self)
+    }
 }
 impl Serialize for NodeIdSetResult {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -107,6 +114,9 @@ impl AnalysisResult for NodeIdSetVecResult {
     fn into_describe_string(self) -> String {
         json_format_single_line!(self.describe())
     }
+    fn is_partial(&self) -> bool {
+        NodeIdSetVecResult::is_partial(self)
+    }
 }
 impl Serialize for NodeIdSetVecResult {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -178,13 +188,9 @@ impl NodeIdSetVecResult {
             .iter()
             .map(|node_set| {
                 if let Some(unshrink_table) = self.unshrink_table.as_ref() {
-                    NodeIdSetResult {
-                        node_set: unshrink_set(node_set, unshrink_table),
-                    }
+                    NodeIdSetResult::new(unshrink_set(node_set, unshrink_table), None)
                 } else {
-                    NodeIdSetResult {
-                        node_set: node_set.clone(),
-                    }
+                    NodeIdSetResult::new(node_set.clone(), None)
                 }
                 .into_pretty_vec(fbas, groupings)
             })
@@ -0,0 +1,195 @@
+//! Reading the output of stellar-core's `quorum?transitive=true` HTTP command, i.e., the FBAS as
+//! seen by one validator.
+
+use super::*;
+
+#[derive(Deserialize)]
+struct RawQuorumReport {
+    transitive: RawTransitiveQuorumReport,
+}
+#[derive(Deserialize)]
+struct RawTransitiveQuorumReport {
+    nodes: Vec<RawCoreNode>,
+}
+#[derive(Deserialize)]
+struct RawCoreNode {
+    node: PublicKey,
+    #[serde(default)]
+    qset: Option<RawCoreQuorumSet>,
+}
+#[derive(Deserialize)]
+struct RawCoreQuorumSet {
+    t: u64,
+    #[serde(default)]
+    v: Vec<RawCoreQuorumSetMember>,
+}
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawCoreQuorumSetMember {
+    Validator(PublicKey),
+    InnerQuorumSet(RawCoreQuorumSet),
+}
+
+impl Fbas {
+    /// Reads an FBAS from the JSON output of stellar-core's `quorum?transitive=true` HTTP
+    /// command. Nodes that are referenced in quorum sets but for which the report contains no
+    /// quorum set are added with an empty quorum set.
+    pub fn from_stellar_core_quorum_json_str(json: &str) -> Self {
+        Self::try_from_stellar_core_quorum_json_str(json)
+            .unwrap_or_else(|e| panic!("Error parsing stellar-core quorum JSON: {}", e))
+    }
+    pub fn from_stellar_core_quorum_json_file(path: &Path) -> Self {
+        Self::try_from_stellar_core_quorum_json_file(path)
+            .unwrap_or_else(|e| panic!("Error parsing stellar-core quorum JSON: {}", e))
+    }
+    /// Like `from_stellar_core_quorum_json_str`, but returns an `Error` instead of panicking on
+    /// bad input.
+    pub fn try_from_stellar_core_quorum_json_str(json: &str) -> Result<Self, Error> {
+        let report: RawQuorumReport = parse_json(json)?;
+        Self::try_from_raw(report.into_raw_fbas())
+    }
+    /// Like `from_stellar_core_quorum_json_file`, but returns an `Error` instead of panicking on
+    /// bad input.
+    pub fn try_from_stellar_core_quorum_json_file(path: &Path) -> Result<Self, Error> {
+        Self::try_from_stellar_core_quorum_json_str(&read_file(path)?)
+    }
+}
+
+impl RawQuorumReport {
+    fn into_raw_fbas(self) -> RawFbas {
+        let mut raw_nodes: Vec<RawNode> = self
+            .transitive
+            .nodes
+            .into_iter()
+            .map(|node| RawNode {
+                public_key: node.node,
+                quorum_set: node.qset.map(|q| q.into_raw()).unwrap_or_default(),
+                isp: None,
+                geo_data: None,
+            })
+            .collect();
+
+        let listed_nodes: HashSet<PublicKey> =
+            raw_nodes.iter().map(|n| n.public_key.clone()).collect();
+        let mut unlisted_nodes: Vec<PublicKey> = raw_nodes
+            .iter()
+            .flat_map(|n| n.quorum_set.contained_public_keys())
+            .filter(|pk| !listed_nodes.contains(pk))
+            .collect();
+        unlisted_nodes.sort();
+        unlisted_nodes.dedup();
+        raw_nodes.extend(unlisted_nodes.into_iter().map(|public_key| RawNode {
+            public_key,
+            quorum_set: RawQuorumSet::default(),
+            isp: None,
+            geo_data: None,
+        }));
+        RawFbas(raw_nodes)
+    }
+}
+impl RawCoreQuorumSet {
+    fn into_raw(self) -> RawQuorumSet {
+        let mut validators = vec![];
+        let mut inner_quorum_sets = vec![];
+        for member in self.v.into_iter() {
+            match member {
+                RawCoreQuorumSetMember::Validator(public_key) => validators.push(public_key),
+                RawCoreQuorumSetMember::InnerQuorumSet(quorum_set) => {
+                    inner_quorum_sets.push(quorum_set.into_raw())
+                }
+            }
+        }
+        RawQuorumSet {
+            threshold: self.t,
+            validators,
+            inner_quorum_sets,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRANSITIVE_QUORUM_REPORT: &str = r#"{
+        "node": "GA",
+        "qset": { "agree": 3, "disagree": 0, "fail_at": 2, "missing": 0, "phase": "EXTERNALIZE" },
+        "transitive": {
+            "critical": null,
+            "intersection": true,
+            "last_check_ledger": 121235,
+            "node_count": 4,
+            "nodes": [
+                {
+                    "distance": 0,
+                    "heard": 121235,
+                    "node": "GA",
+                    "qset": { "t": 2, "v": ["GA", "GB", { "t": 1, "v": ["GC", "GD"] }] }
+                },
+                {
+                    "distance": 1,
+                    "heard": 121235,
+                    "node": "GB",
+                    "qset": { "t": 2, "v": ["GA", "GB", "GC"] }
+                },
+                {
+                    "distance": 1,
+                    "heard": 121235,
+                    "node": "GC",
+                    "qset": { "t": 2, "v": ["GA", "GB", "GC"] }
+                }
+            ]
+        }
+    }"#;
+
+    #[test]
+    fn from_stellar_core_quorum_json_str_reads_nested_quorum_sets() {
+        let fbas = Fbas::from_stellar_core_quorum_json_str(TRANSITIVE_QUORUM_REPORT);
+        let expected = QuorumSet {
+            threshold: 2,
+            validators: vec![0, 1],
+            inner_quorum_sets: vec![QuorumSet {
+                threshold: 1,
+                validators: vec![2, 3],
+                inner_quorum_sets: vec![],
+            }],
+        };
+        assert_eq!(Some(expected), fbas.get_quorum_set(0));
+        assert_eq!(
+            Some(QuorumSet {
+                threshold: 2,
+                validators: vec![0, 1, 2],
+                inner_quorum_sets: vec![],
+            }),
+            fbas.get_quorum_set(1)
+        );
+    }
+
+    #[test]
+    fn from_stellar_core_quorum_json_str_adds_nodes_without_known_quorum_set() {
+        let fbas = Fbas::from_stellar_core_quorum_json_str(TRANSITIVE_QUORUM_REPORT);
+        assert_eq!(4, fbas.number_of_nodes());
+        assert_eq!(Some(3), fbas.get_node_id("GD"));
+        assert_eq!(Some(QuorumSet::new()), fbas.get_quorum_set(3));
+    }
+
+    #[test]
+    fn stellar_core_quorum_json_can_be_analyzed() {
+        let fbas = Fbas::from_stellar_core_quorum_json_str(TRANSITIVE_QUORUM_REPORT);
+        let analysis = Analysis::new(&fbas);
+        assert!(analysis.has_quorum_intersection());
+        assert_eq!(
+            bitsetvec![{0, 1}, {0, 2}, {1, 2}],
+            analysis.minimal_quorums().unwrap()
+        );
+    }
+
+    #[test]
+    fn try_from_stellar_core_quorum_json_str_reports_missing_transitive_data() {
+        let json = r#"{ "node": "GA", "qset": { "agree": 3 } }"#;
+        assert!(matches!(
+            Fbas::try_from_stellar_core_quorum_json_str(json),
+            Err(Error::SchemaMismatch { .. })
+        ));
+    }
+}
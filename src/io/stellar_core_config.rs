@@ -0,0 +1,562 @@
+//! Reading quorum set configurations from stellar-core configuration files (TOML), either from
+//! an explicit `[QUORUM_SET]` section tree or generated from `[[VALIDATORS]]` and
+//! `[[HOME_DOMAINS]]` entries like stellar-core does it.
+
+use super::*;
+use toml::Value;
+
+const DEFAULT_THRESHOLD_PERCENT: i64 = 67;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
+enum ValidatorQuality {
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+#[derive(Clone, Debug)]
+struct ValidatorEntry {
+    public_key: PublicKey,
+    home_domain: String,
+    quality: ValidatorQuality,
+}
+
+impl Fbas {
+    /// Adds a node with public key `public_key` and the quorum set configured in the
+    /// stellar-core configuration `config_toml`. If a node with the same public key already
+    /// exists, its quorum set is replaced. Validators that are referenced in the quorum set but
+    /// not yet part of the FBAS are added as nodes with empty quorum sets.
+    pub fn try_add_stellar_core_config(
+        &mut self,
+        public_key: &str,
+        config_toml: &str,
+    ) -> Result<NodeId, Error> {
+        let raw_quorum_set = raw_quorum_set_from_stellar_core_config(config_toml, public_key)?;
+        let node_id = match self.get_node_id(public_key) {
+            Some(node_id) => node_id,
+            None => self.add_node(Node::new(public_key.to_string())),
+        };
+        for validator in raw_quorum_set.contained_public_keys().into_iter() {
+            if self.get_node_id(&validator).is_none() {
+                self.add_node(Node::new(validator));
+            }
+        }
+        let quorum_set = QuorumSet::from_raw(raw_quorum_set, &self.pk_to_id);
+        self.swap_quorum_set(node_id, quorum_set);
+        Ok(node_id)
+    }
+    /// Like `try_add_stellar_core_config`, but reads the configuration from a file.
+    pub fn try_add_stellar_core_config_file(
+        &mut self,
+        public_key: &str,
+        path: &Path,
+    ) -> Result<NodeId, Error> {
+        self.try_add_stellar_core_config(public_key, &read_file(path)?)
+    }
+    /// Builds an FBAS out of pairs of `(public_key, config_toml)`; see
+    /// `try_add_stellar_core_config`.
+    pub fn try_from_stellar_core_configs(configs: &[(&str, &str)]) -> Result<Self, Error> {
+        let mut fbas = Fbas::new();
+        for (public_key, config_toml) in configs.iter() {
+            fbas.try_add_stellar_core_config(public_key, config_toml)?;
+        }
+        Ok(fbas)
+    }
+}
+
+fn raw_quorum_set_from_stellar_core_config(
+    config_toml: &str,
+    own_public_key: &str,
+) -> Result<RawQuorumSet, Error> {
+    let config: Value = config_toml.parse().map_err(Error::TomlSyntax)?;
+    let node_names = read_node_names(&config)?;
+    match (config.get("QUORUM_SET"), config.get("VALIDATORS")) {
+        (Some(_), Some(_)) => Err(invalid_config(
+            "QUORUM_SET and VALIDATORS can't be used together",
+        )),
+        (Some(quorum_set), None) => {
+            read_explicit_quorum_set(quorum_set, "QUORUM_SET", &node_names, own_public_key)
+        }
+        (None, Some(_)) => {
+            let validators = read_validator_entries(&config)?;
+            Ok(generate_quorum_set(validators)?.normalized())
+        }
+        (None, None) => Err(invalid_config(
+            "neither QUORUM_SET nor VALIDATORS is configured",
+        )),
+    }
+}
+
+/// Reads the mapping of names to public keys from `NODE_NAMES` and `[[VALIDATORS]]`.
+fn read_node_names(config: &Value) -> Result<HashMap<String, PublicKey>, Error> {
+    let mut node_names = HashMap::new();
+    if let Some(entries) = config.get("NODE_NAMES") {
+        for entry in as_array(entries, "NODE_NAMES")?.iter() {
+            let entry = as_str(entry, "NODE_NAMES")?;
+            let mut tokens = entry.split_whitespace();
+            if let (Some(public_key), Some(name)) = (tokens.next(), tokens.next()) {
+                node_names.insert(name.to_string(), public_key.to_string());
+            } else {
+                return Err(invalid_config(&format!(
+                    "NODE_NAMES entry {:?} is not of the form \"PUBLIC_KEY NAME\"",
+                    entry
+                )));
+            }
+        }
+    }
+    if let Some(Value::Array(validators)) = config.get("VALIDATORS") {
+        for validator in validators.iter() {
+            if let (Some(Value::String(name)), Some(Value::String(public_key))) =
+                (validator.get("NAME"), validator.get("PUBLIC_KEY"))
+            {
+                node_names.insert(name.clone(), public_key.clone());
+            }
+        }
+    }
+    Ok(node_names)
+}
+
+fn read_explicit_quorum_set(
+    quorum_set: &Value,
+    key_path: &str,
+    node_names: &HashMap<String, PublicKey>,
+    own_public_key: &str,
+) -> Result<RawQuorumSet, Error> {
+    let table = quorum_set
+        .as_table()
+        .ok_or_else(|| invalid_config(&format!("{} must be a table", key_path)))?;
+
+    let mut validators = vec![];
+    let mut inner_quorum_sets = vec![];
+    let mut threshold_percent = DEFAULT_THRESHOLD_PERCENT;
+    for (key, value) in table.iter() {
+        let sub_key_path = format!("{}.{}", key_path, key);
+        match key.as_str() {
+            "THRESHOLD_PERCENT" => {
+                threshold_percent = value.as_integer().ok_or_else(|| {
+                    invalid_config(&format!("{} must be an integer", sub_key_path))
+                })?;
+            }
+            "VALIDATORS" => {
+                for validator in as_array(value, &sub_key_path)?.iter() {
+                    validators.push(resolve_validator(
+                        as_str(validator, &sub_key_path)?,
+                        node_names,
+                        own_public_key,
+                    )?);
+                }
+            }
+            _ => {
+                inner_quorum_sets.push(read_explicit_quorum_set(
+                    value,
+                    &sub_key_path,
+                    node_names,
+                    own_public_key,
+                )?);
+            }
+        }
+    }
+    if !(1..=100).contains(&threshold_percent) {
+        return Err(invalid_config(&format!(
+            "{}.THRESHOLD_PERCENT must be between 1 and 100",
+            key_path
+        )));
+    }
+    let size = (validators.len() + inner_quorum_sets.len()) as u64;
+    let threshold = if size == 0 {
+        0
+    } else {
+        // rounding up, like stellar-core
+        1 + (size * threshold_percent as u64 - 1) / 100
+    };
+    Ok(RawQuorumSet {
+        threshold,
+        validators,
+        inner_quorum_sets,
+    })
+}
+
+/// Validators can be given as `"PUBLIC_KEY [NAME]"` or as `"$NAME"`, with `$self` meaning the
+/// configured node itself.
+fn resolve_validator(
+    validator: &str,
+    node_names: &HashMap<String, PublicKey>,
+    own_public_key: &str,
+) -> Result<PublicKey, Error> {
+    let first_token = validator.split_whitespace().next().unwrap_or("");
+    if first_token == "$self" {
+        Ok(own_public_key.to_string())
+    } else if let Some(name) = first_token.strip_prefix('$') {
+        node_names
+            .get(name)
+            .cloned()
+            .ok_or_else(|| invalid_config(&format!("unknown validator name {:?}", name)))
+    } else if first_token.is_empty() {
+        Err(invalid_config("empty validator entry"))
+    } else {
+        Ok(first_token.to_string())
+    }
+}
+
+fn read_validator_entries(config: &Value) -> Result<Vec<ValidatorEntry>, Error> {
+    let mut home_domain_qualities: HashMap<String, ValidatorQuality> = HashMap::new();
+    if let Some(home_domains) = config.get("HOME_DOMAINS") {
+        for home_domain in as_array(home_domains, "HOME_DOMAINS")?.iter() {
+            let name = get_str(home_domain, "HOME_DOMAIN", "HOME_DOMAINS")?;
+            let quality = read_quality(get_str(home_domain, "QUALITY", "HOME_DOMAINS")?)?;
+            home_domain_qualities.insert(name.to_string(), quality);
+        }
+    }
+    let mut validators = vec![];
+    for validator in as_array(config.get("VALIDATORS").unwrap(), "VALIDATORS")?.iter() {
+        let public_key = get_str(validator, "PUBLIC_KEY", "VALIDATORS")?.to_string();
+        let home_domain = get_str(validator, "HOME_DOMAIN", "VALIDATORS")?.to_string();
+        let own_quality = match validator.get("QUALITY") {
+            Some(quality) => Some(read_quality(as_str(quality, "VALIDATORS.QUALITY")?)?),
+            None => None,
+        };
+        let quality = match (own_quality, home_domain_qualities.get(&home_domain)) {
+            (Some(_), Some(_)) => {
+                return Err(invalid_config(&format!(
+                    "QUALITY of {} is set for both the validator and its HOME_DOMAIN",
+                    public_key
+                )))
+            }
+            (Some(quality), None) | (None, Some(&quality)) => quality,
+            (None, None) => {
+                return Err(invalid_config(&format!(
+                    "QUALITY of {} is set neither for the validator nor for its HOME_DOMAIN",
+                    public_key
+                )))
+            }
+        };
+        validators.push(ValidatorEntry {
+            public_key,
+            home_domain,
+            quality,
+        });
+    }
+    Ok(validators)
+}
+
+fn read_quality(quality: &str) -> Result<ValidatorQuality, Error> {
+    match quality {
+        "LOW" => Ok(ValidatorQuality::Low),
+        "MEDIUM" => Ok(ValidatorQuality::Medium),
+        "HIGH" => Ok(ValidatorQuality::High),
+        "CRITICAL" => Ok(ValidatorQuality::Critical),
+        _ => Err(invalid_config(&format!("unknown QUALITY {:?}", quality))),
+    }
+}
+
+/// Reproduces stellar-core's automatic quorum set generation: validators of the same home domain
+/// form an inner quorum set with a simple majority threshold; all inner quorum sets of the same
+/// quality are combined with a 67% threshold, with the group of the next-lower quality as one
+/// additional member.
+fn generate_quorum_set(mut validators: Vec<ValidatorEntry>) -> Result<RawQuorumSet, Error> {
+    validators.sort_by(|l, r| {
+        r.quality
+            .cmp(&l.quality)
+            .then_with(|| l.home_domain.cmp(&r.home_domain))
+    });
+    if validators.is_empty() {
+        Ok(RawQuorumSet::default())
+    } else {
+        generate_quorum_set_for_quality(&validators, validators[0].quality)
+    }
+}
+fn generate_quorum_set_for_quality(
+    validators: &[ValidatorEntry],
+    quality: ValidatorQuality,
+) -> Result<RawQuorumSet, Error> {
+    let mut quorum_set = RawQuorumSet::default();
+    let mut rest = validators;
+    while let Some(first) = rest.first().filter(|v| v.quality == quality) {
+        let organization_size = rest
+            .iter()
+            .take_while(|v| v.home_domain == first.home_domain)
+            .count();
+        let (organization, remaining) = rest.split_at(organization_size);
+        if let Some(other) = organization.iter().find(|v| v.quality != quality) {
+            return Err(invalid_config(&format!(
+                "validators {} and {} of home domain {} must have the same quality",
+                first.public_key, other.public_key, first.home_domain
+            )));
+        }
+        if organization.len() < 3 && quality >= ValidatorQuality::High {
+            return Err(invalid_config(&format!(
+                "home domain {} has high quality and must have at least 3 validators",
+                first.home_domain
+            )));
+        }
+        let validators: Vec<PublicKey> =
+            organization.iter().map(|v| v.public_key.clone()).collect();
+        quorum_set.inner_quorum_sets.push(RawQuorumSet {
+            threshold: simple_majority(validators.len()),
+            validators,
+            inner_quorum_sets: vec![],
+        });
+        rest = remaining;
+    }
+    if let Some(first) = rest.first() {
+        let lower_quality_quorum_set = generate_quorum_set_for_quality(rest, first.quality)?;
+        quorum_set.inner_quorum_sets.push(lower_quality_quorum_set);
+    }
+    quorum_set.threshold = byzantine_fault_tolerance(quorum_set.inner_quorum_sets.len());
+    Ok(quorum_set)
+}
+fn simple_majority(size: usize) -> u64 {
+    if size == 0 {
+        0
+    } else {
+        (size / 2 + 1) as u64
+    }
+}
+fn byzantine_fault_tolerance(size: usize) -> u64 {
+    if size == 0 {
+        0
+    } else {
+        (1 + (size * 2 - 1) / 3) as u64
+    }
+}
+
+impl RawQuorumSet {
+    /// Simplifies the quorum set like stellar-core does it: inner quorum sets with threshold 1
+    /// and a single validator become validators, and quorum sets with threshold 1 and a single
+    /// inner quorum set become that inner quorum set.
+    fn normalized(self) -> Self {
+        let mut validators = self.validators;
+        let mut inner_quorum_sets = vec![];
+        for inner_quorum_set in self.inner_quorum_sets.into_iter() {
+            let inner_quorum_set = inner_quorum_set.normalized();
+            if inner_quorum_set.threshold == 1
+                && inner_quorum_set.validators.len() == 1
+                && inner_quorum_set.inner_quorum_sets.is_empty()
+            {
+                validators.extend(inner_quorum_set.validators);
+            } else {
+                inner_quorum_sets.push(inner_quorum_set);
+            }
+        }
+        if self.threshold == 1 && validators.is_empty() && inner_quorum_sets.len() == 1 {
+            inner_quorum_sets.pop().unwrap()
+        } else {
+            RawQuorumSet {
+                threshold: self.threshold,
+                validators,
+                inner_quorum_sets,
+            }
+        }
+    }
+}
+
+fn invalid_config(message: &str) -> Error {
+    Error::InvalidStellarCoreConfig(message.to_string())
+}
+fn as_array<'a>(value: &'a Value, key_path: &str) -> Result<&'a Vec<Value>, Error> {
+    value
+        .as_array()
+        .ok_or_else(|| invalid_config(&format!("{} must be an array", key_path)))
+}
+fn as_str<'a>(value: &'a Value, key_path: &str) -> Result<&'a str, Error> {
+    value
+        .as_str()
+        .ok_or_else(|| invalid_config(&format!("{} must be a string", key_path)))
+}
+fn get_str<'a>(table: &'a Value, key: &str, key_path: &str) -> Result<&'a str, Error> {
+    let key_path = format!("{}.{}", key_path, key);
+    let value = table
+        .get(key)
+        .ok_or_else(|| invalid_config(&format!("{} is missing", key_path)))?;
+    as_str(value, &key_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flat_raw_qset(validators: &[&str], threshold: u64) -> RawQuorumSet {
+        RawQuorumSet {
+            threshold,
+            validators: validators.iter().map(|v| v.to_string()).collect(),
+            inner_quorum_sets: vec![],
+        }
+    }
+
+    #[test]
+    fn explicit_quorum_set_with_inner_quorum_sets() {
+        let config = r#"
+            NODE_NAMES = ["GC sdf3"]
+
+            [QUORUM_SET]
+            THRESHOLD_PERCENT = 66
+            VALIDATORS = ["$self", "GB sdf2", "$sdf3"]
+
+            [QUORUM_SET.sub]
+            THRESHOLD_PERCENT = 100
+            VALIDATORS = ["GD", "GE"]
+        "#;
+        let expected = RawQuorumSet {
+            threshold: 3,
+            validators: vec!["GA".to_string(), "GB".to_string(), "GC".to_string()],
+            inner_quorum_sets: vec![flat_raw_qset(&["GD", "GE"], 2)],
+        };
+        let actual = raw_quorum_set_from_stellar_core_config(config, "GA").unwrap();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn explicit_quorum_set_uses_default_threshold_percent() {
+        let config = r#"
+            [QUORUM_SET]
+            VALIDATORS = ["GA", "GB", "GC", "GD"]
+        "#;
+        let actual = raw_quorum_set_from_stellar_core_config(config, "GA").unwrap();
+        assert_eq!(flat_raw_qset(&["GA", "GB", "GC", "GD"], 3), actual);
+    }
+
+    #[test]
+    fn automatic_quorum_set_by_quality() {
+        let config = r#"
+            [[HOME_DOMAINS]]
+            HOME_DOMAIN = "a.com"
+            QUALITY = "HIGH"
+
+            [[HOME_DOMAINS]]
+            HOME_DOMAIN = "b.com"
+            QUALITY = "HIGH"
+
+            [[VALIDATORS]]
+            NAME = "a1"
+            HOME_DOMAIN = "a.com"
+            PUBLIC_KEY = "GA1"
+
+            [[VALIDATORS]]
+            NAME = "d1"
+            HOME_DOMAIN = "d.com"
+            PUBLIC_KEY = "GD1"
+            QUALITY = "LOW"
+
+            [[VALIDATORS]]
+            NAME = "a2"
+            HOME_DOMAIN = "a.com"
+            PUBLIC_KEY = "GA2"
+
+            [[VALIDATORS]]
+            NAME = "a3"
+            HOME_DOMAIN = "a.com"
+            PUBLIC_KEY = "GA3"
+
+            [[VALIDATORS]]
+            NAME = "b1"
+            HOME_DOMAIN = "b.com"
+            PUBLIC_KEY = "GB1"
+
+            [[VALIDATORS]]
+            NAME = "b2"
+            HOME_DOMAIN = "b.com"
+            PUBLIC_KEY = "GB2"
+
+            [[VALIDATORS]]
+            NAME = "b3"
+            HOME_DOMAIN = "b.com"
+            PUBLIC_KEY = "GB3"
+
+            [[VALIDATORS]]
+            NAME = "c1"
+            HOME_DOMAIN = "c.com"
+            PUBLIC_KEY = "GC1"
+            QUALITY = "MEDIUM"
+
+            [[VALIDATORS]]
+            NAME = "d2"
+            HOME_DOMAIN = "d.com"
+            PUBLIC_KEY = "GD2"
+            QUALITY = "LOW"
+        "#;
+        let expected = RawQuorumSet {
+            threshold: 2,
+            validators: vec![],
+            inner_quorum_sets: vec![
+                flat_raw_qset(&["GA1", "GA2", "GA3"], 2),
+                flat_raw_qset(&["GB1", "GB2", "GB3"], 2),
+                RawQuorumSet {
+                    threshold: 2,
+                    validators: vec!["GC1".to_string()],
+                    inner_quorum_sets: vec![flat_raw_qset(&["GD1", "GD2"], 2)],
+                },
+            ],
+        };
+        let actual = raw_quorum_set_from_stellar_core_config(config, "GA1").unwrap();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn automatic_quorum_set_requires_redundancy_for_high_quality() {
+        let config = r#"
+            [[VALIDATORS]]
+            NAME = "a1"
+            HOME_DOMAIN = "a.com"
+            PUBLIC_KEY = "GA1"
+            QUALITY = "HIGH"
+        "#;
+        assert!(matches!(
+            raw_quorum_set_from_stellar_core_config(config, "GA1"),
+            Err(Error::InvalidStellarCoreConfig(_))
+        ));
+    }
+
+    #[test]
+    fn invalid_toml_is_reported() {
+        assert!(matches!(
+            raw_quorum_set_from_stellar_core_config("[QUORUM_SET", "GA"),
+            Err(Error::TomlSyntax(_))
+        ));
+    }
+
+    #[test]
+    fn add_stellar_core_config_replaces_quorum_set_and_adds_validators() {
+        let mut fbas = Fbas::from_json_file(Path::new("test_data/correct_trivial.json"));
+        let public_key = fbas.nodes[0].public_key.clone();
+        let other_public_key = fbas.nodes[1].public_key.clone();
+        let config = format!(
+            r#"
+            [QUORUM_SET]
+            THRESHOLD_PERCENT = 100
+            VALIDATORS = ["$self", "{}", "GNEW"]
+            "#,
+            other_public_key
+        );
+        let node_id = fbas
+            .try_add_stellar_core_config(&public_key, &config)
+            .unwrap();
+        assert_eq!(0, node_id);
+        assert_eq!(4, fbas.number_of_nodes());
+        assert_eq!(
+            Some(QuorumSet {
+                threshold: 3,
+                validators: vec![0, 1, 3],
+                inner_quorum_sets: vec![],
+            }),
+            fbas.get_quorum_set(0)
+        );
+    }
+
+    #[test]
+    fn fbas_from_stellar_core_configs_can_be_analyzed() {
+        let config = r#"
+            [QUORUM_SET]
+            THRESHOLD_PERCENT = 66
+            VALIDATORS = ["GA", "GB", "GC"]
+        "#;
+        let fbas =
+            Fbas::try_from_stellar_core_configs(&[("GA", config), ("GB", config), ("GC", config)])
+                .unwrap();
+        let analysis = Analysis::new(&fbas);
+        assert!(analysis.has_quorum_intersection());
+        assert_eq!(
+            bitsetvec![{0, 1}, {0, 2}, {1, 2}],
+            analysis.minimal_quorums().unwrap()
+        );
+    }
+}
@@ -0,0 +1,268 @@
+//! Exporting the trust graph of an FBAS (an edge `a -> b` for each node `b` contained in the
+//! quorum set of node `a`) in Graphviz DOT and GraphML formats, for visualization.
+
+use super::*;
+use std::fmt::Write;
+
+/// Optional information with which the nodes of an exported trust graph are annotated.
+/// Strongly connected components and satisfiability are always derived from the FBAS itself.
+#[derive(Clone, Debug, Default)]
+pub struct TrustGraphAnnotations<'a> {
+    /// Annotate nodes with the name of the organization (or other grouping) they belong to.
+    pub organizations: Option<&'a Groupings<'a>>,
+    /// Mark nodes as belonging to the top tier, e.g., as found via `Analysis::top_tier`.
+    pub top_tier: Option<NodeIdSet>,
+    /// Highlight a set of nodes, e.g., one of the minimal blocking or splitting sets.
+    pub highlighted: Option<NodeIdSet>,
+}
+
+struct TrustGraphNode {
+    public_key: PublicKey,
+    organization: Option<String>,
+    top_tier: bool,
+    scc: usize,
+    satisfiable: bool,
+    highlighted: bool,
+    successors: Vec<NodeId>,
+}
+
+impl Fbas {
+    /// Trust graph of the FBAS in Graphviz DOT format. Nodes are identified by their node IDs
+    /// and labeled with their public keys; top-tier nodes are drawn bold, unsatisfiable nodes
+    /// dashed and highlighted nodes filled.
+    pub fn to_dot_string(&self, annotations: &TrustGraphAnnotations) -> String {
+        let nodes = self.trust_graph_nodes(annotations);
+        let mut dot = String::from("digraph fbas {\n");
+        for (node_id, node) in nodes.iter().enumerate() {
+            let mut attributes = vec![
+                format!("label={}", dot_quoted(&node.public_key)),
+                format!("public_key={}", dot_quoted(&node.public_key)),
+            ];
+            if let Some(organization) = &node.organization {
+                attributes.push(format!("organization={}", dot_quoted(organization)));
+            }
+            attributes.push(format!("scc={}", node.scc));
+            attributes.push(format!("satisfiable={}", node.satisfiable));
+            attributes.push(format!("top_tier={}", node.top_tier));
+            attributes.push(format!("highlighted={}", node.highlighted));
+            let mut styles = vec![];
+            if node.top_tier {
+                styles.push("bold");
+            }
+            if !node.satisfiable {
+                styles.push("dashed");
+            }
+            if node.highlighted {
+                styles.push("filled");
+                attributes.push("fillcolor=\"red\"".to_string());
+            }
+            if !styles.is_empty() {
+                attributes.push(format!("style={}", dot_quoted(&styles.join(","))));
+            }
+            writeln!(dot, "  {} [{}];", node_id, attributes.join(", ")).unwrap();
+        }
+        for (node_id, node) in nodes.iter().enumerate() {
+            for successor in node.successors.iter() {
+                writeln!(dot, "  {} -> {};", node_id, successor).unwrap();
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+    /// Trust graph of the FBAS in GraphML format, with node annotations as GraphML data keys.
+    pub fn to_graphml_string(&self, annotations: &TrustGraphAnnotations) -> String {
+        let nodes = self.trust_graph_nodes(annotations);
+        let mut graphml = String::from(concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n",
+            "  <key id=\"public_key\" for=\"node\" attr.name=\"public_key\" attr.type=\"string\"/>\n",
+            "  <key id=\"organization\" for=\"node\" attr.name=\"organization\" attr.type=\"string\"/>\n",
+            "  <key id=\"scc\" for=\"node\" attr.name=\"scc\" attr.type=\"int\"/>\n",
+            "  <key id=\"satisfiable\" for=\"node\" attr.name=\"satisfiable\" attr.type=\"boolean\"/>\n",
+            "  <key id=\"top_tier\" for=\"node\" attr.name=\"top_tier\" attr.type=\"boolean\"/>\n",
+            "  <key id=\"highlighted\" for=\"node\" attr.name=\"highlighted\" attr.type=\"boolean\"/>\n",
+            "  <graph id=\"fbas\" edgedefault=\"directed\">\n",
+        ));
+        for (node_id, node) in nodes.iter().enumerate() {
+            writeln!(graphml, "    <node id=\"n{}\">", node_id).unwrap();
+            writeln!(
+                graphml,
+                "      <data key=\"public_key\">{}</data>",
+                xml_escaped(&node.public_key)
+            )
+            .unwrap();
+            if let Some(organization) = &node.organization {
+                writeln!(
+                    graphml,
+                    "      <data key=\"organization\">{}</data>",
+                    xml_escaped(organization)
+                )
+                .unwrap();
+            }
+            writeln!(graphml, "      <data key=\"scc\">{}</data>", node.scc).unwrap();
+            writeln!(
+                graphml,
+                "      <data key=\"satisfiable\">{}</data>",
+                node.satisfiable
+            )
+            .unwrap();
+            writeln!(
+                graphml,
+                "      <data key=\"top_tier\">{}</data>",
+                node.top_tier
+            )
+            .unwrap();
+            writeln!(
+                graphml,
+                "      <data key=\"highlighted\">{}</data>",
+                node.highlighted
+            )
+            .unwrap();
+            graphml.push_str("    </node>\n");
+        }
+        for (node_id, node) in nodes.iter().enumerate() {
+            for successor in node.successors.iter() {
+                writeln!(
+                    graphml,
+                    "    <edge source=\"n{}\" target=\"n{}\"/>",
+                    node_id, successor
+                )
+                .unwrap();
+            }
+        }
+        graphml.push_str("  </graph>\n</graphml>\n");
+        graphml
+    }
+    fn trust_graph_nodes(&self, annotations: &TrustGraphAnnotations) -> Vec<TrustGraphNode> {
+        let mut scc_of_node = vec![0; self.nodes.len()];
+        for (scc, nodes) in self.strongly_connected_components().iter().enumerate() {
+            for node_id in nodes.iter() {
+                scc_of_node[node_id] = scc;
+            }
+        }
+        let satisfiable_nodes = self.satisfiable_nodes();
+        let is_in = |o_set: &Option<NodeIdSet>, node_id| {
+            o_set.as_ref().is_some_and(|set| set.contains(node_id))
+        };
+        self.nodes
+            .iter()
+            .enumerate()
+            .map(|(node_id, node)| TrustGraphNode {
+                public_key: node.public_key.clone(),
+                organization: annotations
+                    .organizations
+                    .and_then(|orgs| orgs.get_by_member(node_id))
+                    .map(|org| org.name.clone()),
+                top_tier: is_in(&annotations.top_tier, node_id),
+                scc: scc_of_node[node_id],
+                satisfiable: satisfiable_nodes.contains(node_id),
+                highlighted: is_in(&annotations.highlighted, node_id),
+                successors: node.quorum_set.contained_nodes().into_iter().collect(),
+            })
+            .collect()
+    }
+}
+
+fn dot_quoted(string: &str) -> String {
+    format!("\"{}\"", string.replace('\\', "\\\\").replace('"', "\\\""))
+}
+fn xml_escaped(string: &str) -> String {
+    string
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_fbas() -> Fbas {
+        Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1"] }
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1"] }
+            },
+            {
+                "publicKey": "n\"2\"",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n3"] }
+            },
+            {
+                "publicKey": "n3"
+            }
+        ]"#,
+        )
+    }
+
+    #[test]
+    fn to_dot_string_contains_nodes_and_edges() {
+        let fbas = test_fbas();
+        let dot = fbas.to_dot_string(&TrustGraphAnnotations::default());
+        assert!(dot.starts_with("digraph fbas {\n"));
+        assert!(dot.contains("  0 -> 1;\n"));
+        assert!(dot.contains("  2 -> 3;\n"));
+        assert!(!dot.contains("  3 -> "));
+        assert!(dot.contains("label=\"n\\\"2\\\"\""));
+        assert!(dot.ends_with("}\n"));
+    }
+
+    #[test]
+    fn to_dot_string_annotates_satisfiability_top_tier_and_highlighted_nodes() {
+        let fbas = test_fbas();
+        let annotations = TrustGraphAnnotations {
+            top_tier: Some(bitset![0, 1]),
+            highlighted: Some(bitset![1]),
+            ..Default::default()
+        };
+        let dot = fbas.to_dot_string(&annotations);
+        let node_line = |node_id: usize| {
+            dot.lines()
+                .find(|line| line.starts_with(&format!("  {} [", node_id)))
+                .unwrap()
+                .to_string()
+        };
+        assert!(node_line(0).contains("top_tier=true"));
+        assert!(node_line(0).contains("highlighted=false"));
+        assert!(node_line(1).contains("style=\"bold,filled\""));
+        assert!(node_line(2).contains("satisfiable=false"));
+        assert!(node_line(2).contains("style=\"dashed\""));
+    }
+
+    #[test]
+    fn to_graphml_string_annotates_organizations_and_sccs() {
+        let fbas = test_fbas();
+        let organizations = Groupings::organizations_from_json_str(
+            r#"[
+            {
+                "id": "o0",
+                "name": "Org & Co",
+                "validators": ["n0", "n1"]
+            }
+        ]"#,
+            &fbas,
+        );
+        let annotations = TrustGraphAnnotations {
+            organizations: Some(&organizations),
+            ..Default::default()
+        };
+        let graphml = fbas.to_graphml_string(&annotations);
+        assert!(graphml.contains("<data key=\"organization\">Org &amp; Co</data>"));
+        assert!(graphml.contains("<data key=\"public_key\">n&quot;2&quot;</data>"));
+        assert!(graphml.contains("<edge source=\"n2\" target=\"n3\"/>"));
+        assert_eq!(2, graphml.matches("<data key=\"organization\">").count());
+        let scc_data: Vec<&str> = graphml
+            .lines()
+            .filter(|line| line.contains("<data key=\"scc\">"))
+            .collect();
+        assert_eq!(scc_data[0], scc_data[1]);
+        assert_ne!(scc_data[0], scc_data[2]);
+        assert_ne!(scc_data[2], scc_data[3]);
+    }
+}
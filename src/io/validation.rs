@@ -0,0 +1,442 @@
+use super::*;
+
+/// How bad a `Finding` is.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Severity {
+    /// Unusual, but possibly intended.
+    Info,
+    /// Most likely a misconfiguration, but one that is analyzed as written, e.g., a quorum set
+    /// that can never be satisfied.
+    Warning,
+    /// Configuration that is silently reinterpreted when loading or analyzing the FBAS, so that
+    /// analysis results don't reflect what is written.
+    Error,
+}
+
+/// The kinds of problems that `Fbas::validate` and friends look for.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FindingKind {
+    /// The quorum set references public keys of nodes that are not part of the FBAS. These
+    /// references are dropped when loading the FBAS.
+    UnknownValidators,
+    /// The threshold is greater than the number of (known) validators and inner quorum sets.
+    ImpossibleThreshold,
+    /// A non-empty quorum set has a threshold of 0, which is interpreted as "trusts no one", i.e.,
+    /// as never satisfied.
+    ZeroThreshold,
+    /// Some validators appear more than once within the same quorum set.
+    DuplicateValidators,
+    /// An inner quorum set contains neither validators nor inner quorum sets.
+    EmptyInnerQuorumSet,
+    /// The node has no quorum set at all (typical for watcher nodes).
+    EmptyQuorumSet,
+    /// The node is not contained in its own quorum set.
+    SelfExclusion,
+    /// The node's quorum set can never be satisfied given the other nodes in the FBAS.
+    UnsatisfiableNode,
+}
+impl FindingKind {
+    pub fn severity(&self) -> Severity {
+        match self {
+            FindingKind::UnknownValidators | FindingKind::ZeroThreshold => Severity::Error,
+            FindingKind::ImpossibleThreshold
+            | FindingKind::DuplicateValidators
+            | FindingKind::EmptyInnerQuorumSet
+            | FindingKind::UnsatisfiableNode => Severity::Warning,
+            FindingKind::EmptyQuorumSet | FindingKind::SelfExclusion => Severity::Info,
+        }
+    }
+}
+
+/// One problem found in the configuration of a node.
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Finding {
+    pub severity: Severity,
+    pub kind: FindingKind,
+    /// Public key of the node whose configuration is affected.
+    pub public_key: PublicKey,
+    /// Location of the problem in the JSON node list, e.g., `[3].quorumSet.innerQuorumSets[0]`.
+    pub json_path: String,
+    /// Further public keys involved in the problem, e.g., the unknown or duplicate validators.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub involved_public_keys: Vec<PublicKey>,
+}
+impl Finding {
+    fn new(
+        kind: FindingKind,
+        public_key: &str,
+        json_path: &str,
+        involved_public_keys: Vec<PublicKey>,
+    ) -> Self {
+        Finding {
+            severity: kind.severity(),
+            kind,
+            public_key: public_key.to_string(),
+            json_path: json_path.to_string(),
+            involved_public_keys,
+        }
+    }
+}
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let description = match self.kind {
+            FindingKind::UnknownValidators => "references unknown nodes",
+            FindingKind::ImpossibleThreshold => {
+                "threshold is greater than the number of validators and inner quorum sets"
+            }
+            FindingKind::ZeroThreshold => "threshold is 0, i.e., node trusts no one",
+            FindingKind::DuplicateValidators => "contains duplicate validators",
+            FindingKind::EmptyInnerQuorumSet => "inner quorum set is empty",
+            FindingKind::EmptyQuorumSet => "quorum set is empty",
+            FindingKind::SelfExclusion => "node is not part of its own quorum set",
+            FindingKind::UnsatisfiableNode => "quorum set can never be satisfied",
+        };
+        let severity = match self.severity {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        write!(
+            f,
+            "{}: {} at {}: {}",
+            severity, self.public_key, self.json_path, description
+        )?;
+        if !self.involved_public_keys.is_empty() {
+            write!(f, " ({})", self.involved_public_keys.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl Fbas {
+    /// Checks the quorum set configurations of all nodes for problems like impossible
+    /// thresholds, duplicate validators or unsatisfiable nodes. References to unknown nodes can't
+    /// be found this way, as they are dropped when loading an FBAS; use `validate_json_str` and
+    /// friends for checking stellarbeat.org JSON data.
+    pub fn validate(&self) -> Vec<Finding> {
+        let mut findings = find_problems_in_raw_fbas(&self.to_raw());
+        findings.append(&mut find_duplicate_validators(self));
+        findings.append(&mut find_unsatisfiable_nodes(self));
+        sort_by_node_id(&mut findings, self);
+        findings
+    }
+    /// Like `validate`, but operates on a JSON string in stellarbeat.org "nodes" format.
+    pub fn validate_json_str(json: &str) -> Result<Vec<Finding>, Error> {
+        let raw_fbas: RawFbas = parse_json(json)?;
+        let mut findings = find_problems_in_raw_fbas(&raw_fbas);
+        let fbas = Fbas::try_from_raw(raw_fbas)?;
+        findings.append(&mut find_duplicate_validators(&fbas));
+        findings.append(&mut find_unsatisfiable_nodes(&fbas));
+        sort_by_node_id(&mut findings, &fbas);
+        Ok(findings)
+    }
+    /// Like `validate`, but operates on a JSON file in stellarbeat.org "nodes" format.
+    pub fn validate_json_file(path: &Path) -> Result<Vec<Finding>, Error> {
+        Self::validate_json_str(&read_file(path)?)
+    }
+}
+
+fn find_problems_in_raw_fbas(raw_fbas: &RawFbas) -> Vec<Finding> {
+    let known_public_keys: HashSet<&PublicKey> = raw_fbas.0.iter().map(|n| &n.public_key).collect();
+    let mut findings = vec![];
+    for (i, raw_node) in raw_fbas.0.iter().enumerate() {
+        let public_key = &raw_node.public_key;
+        let json_path = format!("[{}].quorumSet", i);
+        let quorum_set = &raw_node.quorum_set;
+
+        if quorum_set.is_empty() {
+            findings.push(Finding::new(
+                FindingKind::EmptyQuorumSet,
+                public_key,
+                &json_path,
+                vec![],
+            ));
+            continue;
+        }
+        quorum_set.find_problems(
+            public_key,
+            &json_path,
+            &known_public_keys,
+            true,
+            &mut findings,
+        );
+
+        if !quorum_set.contained_public_keys().contains(public_key) {
+            findings.push(Finding::new(
+                FindingKind::SelfExclusion,
+                public_key,
+                &json_path,
+                vec![],
+            ));
+        }
+    }
+    findings
+}
+
+fn sort_by_node_id(findings: &mut [Finding], fbas: &Fbas) {
+    // stable sort, so that findings for the same node stay in order
+    findings.sort_by_key(|finding| fbas.get_node_id(&finding.public_key));
+}
+
+fn find_duplicate_validators(fbas: &Fbas) -> Vec<Finding> {
+    fbas.nodes
+        .iter()
+        .enumerate()
+        .filter(|(_, node)| node.quorum_set.contains_duplicates())
+        .map(|(node_id, node)| {
+            let mut duplicates: Vec<PublicKey> = node
+                .quorum_set
+                .duplicate_nodes()
+                .iter()
+                .map(|duplicate| fbas.nodes[duplicate].public_key.clone())
+                .collect();
+            duplicates.sort();
+            Finding::new(
+                FindingKind::DuplicateValidators,
+                &node.public_key,
+                &format!("[{}].quorumSet", node_id),
+                duplicates,
+            )
+        })
+        .collect()
+}
+
+fn find_unsatisfiable_nodes(fbas: &Fbas) -> Vec<Finding> {
+    fbas.unsatisfiable_nodes()
+        .into_iter()
+        .map(|node_id| &fbas.nodes[node_id])
+        .filter(|node| node.quorum_set != QuorumSet::new())
+        .map(|node| {
+            let json_path = format!(
+                "[{}].quorumSet",
+                fbas.get_node_id(&node.public_key).unwrap()
+            );
+            Finding::new(
+                FindingKind::UnsatisfiableNode,
+                &node.public_key,
+                &json_path,
+                vec![],
+            )
+        })
+        .collect()
+}
+
+impl RawQuorumSet {
+    fn is_empty(&self) -> bool {
+        self.validators.is_empty() && self.inner_quorum_sets.is_empty()
+    }
+    fn find_problems(
+        &self,
+        public_key: &str,
+        json_path: &str,
+        known_public_keys: &HashSet<&PublicKey>,
+        is_top_level: bool,
+        findings: &mut Vec<Finding>,
+    ) {
+        // unknown validators are dropped when loading the FBAS and hence don't count
+        let number_of_known_validators = self
+            .validators
+            .iter()
+            .filter(|&pk| known_public_keys.contains(pk))
+            .count();
+        let number_of_entries = (number_of_known_validators + self.inner_quorum_sets.len()) as u64;
+        if !is_top_level && self.is_empty() {
+            findings.push(Finding::new(
+                FindingKind::EmptyInnerQuorumSet,
+                public_key,
+                json_path,
+                vec![],
+            ));
+        } else if self.threshold == 0 {
+            findings.push(Finding::new(
+                FindingKind::ZeroThreshold,
+                public_key,
+                json_path,
+                vec![],
+            ));
+        } else if self.threshold > number_of_entries {
+            findings.push(Finding::new(
+                FindingKind::ImpossibleThreshold,
+                public_key,
+                json_path,
+                vec![],
+            ));
+        }
+        let unknown_validators: Vec<PublicKey> = self
+            .validators
+            .iter()
+            .filter(|&pk| !known_public_keys.contains(pk))
+            .cloned()
+            .collect();
+        if !unknown_validators.is_empty() {
+            findings.push(Finding::new(
+                FindingKind::UnknownValidators,
+                public_key,
+                json_path,
+                unknown_validators,
+            ));
+        }
+        for (i, inner_quorum_set) in self.inner_quorum_sets.iter().enumerate() {
+            inner_quorum_set.find_problems(
+                public_key,
+                &format!("{}.innerQuorumSets[{}]", json_path, i),
+                known_public_keys,
+                false,
+                findings,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(findings: &[Finding]) -> Vec<FindingKind> {
+        findings.iter().map(|f| f.kind).collect()
+    }
+
+    #[test]
+    fn validate_correct_fbas_yields_no_findings() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct_trivial.json"));
+        assert_eq!(Vec::<Finding>::new(), fbas.validate());
+    }
+
+    #[test]
+    fn validate_json_finds_unknown_validators() {
+        let input = r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1", "ghost"] }
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1"] }
+            }]"#;
+        let actual = Fbas::validate_json_str(input).unwrap();
+        let expected = vec![Finding {
+            severity: Severity::Error,
+            kind: FindingKind::UnknownValidators,
+            public_key: "n0".to_string(),
+            json_path: "[0].quorumSet".to_string(),
+            involved_public_keys: vec!["ghost".to_string()],
+        }];
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn validate_json_counts_only_known_validators_for_threshold() {
+        let input = r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "ghost"] }
+            }]"#;
+        let actual = Fbas::validate_json_str(input).unwrap();
+        assert_eq!(
+            vec![
+                FindingKind::ImpossibleThreshold,
+                FindingKind::UnknownValidators,
+                FindingKind::UnsatisfiableNode,
+            ],
+            kinds(&actual)
+        );
+    }
+
+    #[test]
+    fn validate_json_finds_threshold_problems() {
+        let input = r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 3, "validators": ["n0", "n1"] }
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": {
+                    "threshold": 2,
+                    "validators": ["n1"],
+                    "innerQuorumSets": [{ "threshold": 0, "validators": ["n0"] }]
+                }
+            }]"#;
+        let actual = Fbas::validate_json_str(input).unwrap();
+        assert_eq!(
+            vec![
+                FindingKind::ImpossibleThreshold,
+                FindingKind::UnsatisfiableNode,
+                FindingKind::ZeroThreshold,
+                FindingKind::UnsatisfiableNode,
+            ],
+            kinds(&actual)
+        );
+        assert_eq!("[1].quorumSet.innerQuorumSets[0]", actual[2].json_path);
+    }
+
+    #[test]
+    fn validate_finds_duplicates_and_empty_inner_quorum_sets() {
+        let input = r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": {
+                    "threshold": 2,
+                    "validators": ["n0", "n1"],
+                    "innerQuorumSets": [
+                        { "threshold": 1, "validators": ["n1"] },
+                        { "threshold": 1, "validators": [] }
+                    ]
+                }
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1"] }
+            }]"#;
+        let fbas = Fbas::from_json_str(input);
+        let actual = fbas.validate();
+        assert_eq!(
+            vec![
+                FindingKind::EmptyInnerQuorumSet,
+                FindingKind::DuplicateValidators,
+            ],
+            kinds(&actual)
+        );
+        assert_eq!(vec!["n1".to_string()], actual[1].involved_public_keys);
+    }
+
+    #[test]
+    fn validate_finds_self_exclusion_and_ignores_watchers() {
+        let input = r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 1, "validators": ["n1"] }
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": { "threshold": 1, "validators": ["n1"] }
+            },
+            {
+                "publicKey": "watcher"
+            }]"#;
+        let fbas = Fbas::from_json_str(input);
+        let actual = fbas.validate();
+        assert_eq!(
+            vec![FindingKind::SelfExclusion, FindingKind::EmptyQuorumSet],
+            kinds(&actual)
+        );
+        assert_eq!(Severity::Info, actual[0].severity);
+    }
+
+    #[test]
+    fn finding_display_lists_involved_public_keys() {
+        let finding = Finding::new(
+            FindingKind::UnknownValidators,
+            "n0",
+            "[0].quorumSet",
+            vec!["ghost1".to_string(), "ghost2".to_string()],
+        );
+        assert_eq!(
+            "error: n0 at [0].quorumSet: references unknown nodes (ghost1, ghost2)",
+            finding.to_string()
+        );
+    }
+}
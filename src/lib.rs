@@ -40,8 +40,13 @@ mod core_types;
 mod io;
 
 pub use analysis::*;
-pub use core_types::{Fbas, Groupings, NodeId, NodeIdSet, QuorumSet};
-pub use io::{AnalysisResult, PrettyQuorumSet};
+pub use core_types::{
+    Fbas, FbasBuilder, FbasDiff, Groupings, MembershipChange, NodeId, NodeIdSet,
+    PublicKeyQuorumSet, QuorumSet, QuorumSetChange,
+};
+pub use io::{
+    AnalysisResult, Error, Finding, FindingKind, PrettyQuorumSet, Severity, TrustGraphAnnotations,
+};
 
 use core_types::*;
 
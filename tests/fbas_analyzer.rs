@@ -66,3 +66,821 @@ fn merge_by_ctry_cli_arg_works() -> Result<(), Box<dyn std::error::Error>> {
     ));
     Ok(())
 }
+
+#[test]
+fn lint_reports_problems() -> Result<(), Box<dyn std::error::Error>> {
+    let fbas_input = r#"[
+            {
+                "publicKey": "Jim",
+                "quorumSet": { "threshold": 3, "validators": ["Jim", "Jon"] }
+            },
+            {
+                "publicKey": "Jon",
+                "quorumSet": { "threshold": 2, "validators": ["Jim", "Jon", "Helen"] }
+            }
+            ]"#;
+    Command::cargo_bin("fbas_analyzer")?
+        .arg("lint")
+        .write_stdin(fbas_input.as_bytes())
+        .assert()
+        .code(2)
+        .stdout(predicate::str::contains(
+            "warning: Jim at [0].quorumSet: threshold is greater than",
+        ))
+        .stdout(predicate::str::contains(
+            "error: Jon at [1].quorumSet: references unknown nodes (Helen)",
+        ));
+    Ok(())
+}
+
+#[test]
+fn lint_succeeds_with_only_warnings() -> Result<(), Box<dyn std::error::Error>> {
+    let fbas_input = r#"[
+            {
+                "publicKey": "Jim",
+                "quorumSet": { "threshold": 3, "validators": ["Jim", "Jon"] }
+            },
+            {
+                "publicKey": "Jon",
+                "quorumSet": { "threshold": 2, "validators": ["Jim", "Jon"] }
+            }
+            ]"#;
+    Command::cargo_bin("fbas_analyzer")?
+        .arg("lint")
+        .write_stdin(fbas_input.as_bytes())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "warning: Jim at [0].quorumSet: threshold is greater than",
+        ));
+    Ok(())
+}
+
+#[test]
+fn lint_succeeds_without_errors() -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("fbas_analyzer")?
+        .arg("lint")
+        .arg("test_data/correct_trivial.json")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Found 0 errors"));
+    Ok(())
+}
+
+#[test]
+fn stellar_core_quorum_json_can_be_analyzed() -> Result<(), Box<dyn std::error::Error>> {
+    let quorum_json = r#"{
+        "node": "Jim",
+        "transitive": {
+            "nodes": [
+                { "node": "Jim", "qset": { "t": 2, "v": ["Jim", "Jon", "Bob"] } },
+                { "node": "Jon", "qset": { "t": 2, "v": ["Jim", "Jon", "Bob"] } },
+                { "node": "Bob", "qset": { "t": 2, "v": ["Jim", "Jon", "Bob"] } }
+            ]
+        }
+    }"#;
+    Command::cargo_bin("fbas_analyzer")?
+        .arg("--stellar-core")
+        .arg("-q")
+        .arg("--results-only")
+        .write_stdin(quorum_json.as_bytes())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "minimal_quorums: [[0,1],[0,2],[1,2]]",
+        ))
+        .stdout(predicate::str::contains("has_quorum_intersection: true"));
+    Ok(())
+}
+
+#[test]
+fn stellar_core_config_overrides_quorum_set() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("fbas_analyzer")?;
+    cmd.arg("test_data/broken_trivial.json")
+        .arg("--stellar-core-config")
+        .arg("GCGB2S2KGYARPVIA37HYZXVRM2YZUEXA6S33ZU5BUDC6THSB62LZSTYH=test_data/stellar_core_trivial.cfg")
+        .arg("-q")
+        .arg("--results-only");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "minimal_quorums: [[0,1],[0,2],[1,2]]",
+        ))
+        .stdout(predicate::str::contains("has_quorum_intersection: true"));
+    Ok(())
+}
+
+#[test]
+fn export_outputs_annotated_dot_graph() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("fbas_analyzer")?;
+    cmd.arg("export")
+        .arg("test_data/correct_trivial.json")
+        .arg("--highlight-blocking-set");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::starts_with("digraph fbas {"))
+        .stdout(predicate::str::contains("0 -> 1;"))
+        .stdout(predicate::str::contains("highlighted=true"));
+    Ok(())
+}
+
+#[test]
+fn timeout_marks_results_as_partial() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("fbas_analyzer")?;
+    cmd.arg("test_data/broken.json")
+        .arg("-q")
+        .arg("--timeout")
+        .arg("0")
+        .arg("--results-only");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("minimal_quorums_partial: true"))
+        .stderr(predicate::str::contains("Analysis timed out"));
+    Ok(())
+}
+
+#[test]
+fn progress_bar_does_not_change_results() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("fbas_analyzer")?;
+    cmd.arg("test_data/correct.json")
+        .arg("-a")
+        .arg("--progress")
+        .arg("--results-only");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("has_quorum_intersection: true"))
+        .stdout(predicate::str::contains(
+            "minimal_splitting_sets: [[0],[1],[10]]",
+        ));
+    Ok(())
+}
+
+#[test]
+fn multi_threaded_analysis_yields_same_results() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("fbas_analyzer")?;
+    cmd.arg("test_data/correct.json")
+        .arg("-a")
+        .arg("--threads")
+        .arg("4")
+        .arg("--results-only");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "minimal_quorums: [[0,1],[0,10],[1,10]]",
+        ))
+        .stdout(predicate::str::contains(
+            "minimal_splitting_sets: [[0],[1],[10]]",
+        ));
+    Ok(())
+}
+
+#[test]
+fn cached_results_are_reused_across_runs() -> Result<(), Box<dyn std::error::Error>> {
+    let cache_path = std::env::temp_dir().join(format!(
+        "fbas_analyzer_cli_test_cache_{}.json",
+        std::process::id()
+    ));
+    let mut cmd = Command::cargo_bin("fbas_analyzer")?;
+    cmd.arg("test_data/correct.json")
+        .arg("-a")
+        .arg("--cache")
+        .arg(&cache_path)
+        .arg("--results-only");
+    cmd.assert().success().stdout(predicate::str::contains(
+        "minimal_splitting_sets: [[0],[1],[10]]",
+    ));
+
+    let mut cmd = Command::cargo_bin("fbas_analyzer")?;
+    cmd.arg("test_data/correct.json")
+        .arg("-a")
+        .arg("--cache")
+        .arg(&cache_path)
+        .arg("--timeout")
+        .arg("0")
+        .arg("--results-only");
+    let assert = cmd.assert();
+    std::fs::remove_file(&cache_path)?;
+    assert
+        .success()
+        .stdout(predicate::str::contains(
+            "minimal_splitting_sets: [[0],[1],[10]]",
+        ))
+        .stdout(predicate::str::contains("_partial").not());
+    Ok(())
+}
+
+#[test]
+fn sat_backend_outputs_nonintersecting_quorums() -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("fbas_analyzer")?
+        .arg("test_data/broken.json")
+        .arg("--qi-backend")
+        .arg("sat")
+        .arg("--results-only")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("has_quorum_intersection: false"))
+        .stdout(predicate::str::contains("nonintersecting_quorums: [["));
+    Command::cargo_bin("fbas_analyzer")?
+        .arg("test_data/correct.json")
+        .arg("--qi-backend")
+        .arg("sat")
+        .arg("--results-only")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("has_quorum_intersection: true"))
+        .stdout(predicate::str::contains("nonintersecting_quorums").not());
+    Ok(())
+}
+
+#[test]
+fn sat_backend_cant_be_combined_with_timeout() -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("fbas_analyzer")?
+        .arg("test_data/correct.json")
+        .arg("--qi-backend")
+        .arg("sat")
+        .arg("--timeout")
+        .arg("10")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--timeout can't be combined"));
+    Ok(())
+}
+
+#[test]
+fn assume_faulty_reports_intact_and_befouled_nodes() -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("fbas_analyzer")?
+        .arg("test_data/correct_trivial.json")
+        .arg("--assume-faulty")
+        .arg("GCM6QMP3DLRPTAZW2UZPCPX2LF3SXWXKPMP3GKFZBDSF3QZGV2G5QSTK")
+        .arg("--results-only")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("intact_nodes: []"))
+        .stdout(predicate::str::contains("befouled_nodes: [0,1,2]"))
+        .stdout(predicate::str::contains(
+            "has_quorum_intersection_despite_faulty: false",
+        ))
+        .stdout(predicate::str::contains(
+            "has_quorum_availability_despite_faulty: true",
+        ));
+    Command::cargo_bin("fbas_analyzer")?
+        .arg("test_data/correct_trivial.json")
+        .arg("--assume-faulty")
+        .arg("unknown")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown node or group"));
+    Ok(())
+}
+
+#[test]
+fn for_node_reports_quorums_and_blocking_sets_of_node() -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("fbas_analyzer")?
+        .arg("test_data/correct_trivial.json")
+        .arg("--for-node")
+        .arg("GCGB2S2KGYARPVIA37HYZXVRM2YZUEXA6S33ZU5BUDC6THSB62LZSTYH")
+        .arg("--results-only")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "minimal_quorums_containing_node: [[0,1],[0,2]]",
+        ))
+        .stdout(predicate::str::contains(
+            "minimal_blocking_sets_for_node: [[1,2]]",
+        ));
+    Ok(())
+}
+
+#[test]
+fn minimal_dsets_are_reported() -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("fbas_analyzer")?
+        .arg("test_data/correct_trivial.json")
+        .arg("--minimal-dsets")
+        .arg("--results-only")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("minimal_dsets: [[]]"));
+    Ok(())
+}
+
+#[test]
+fn cascade_reports_surviving_quorums() -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("fbas_analyzer")?
+        .arg("--results-only")
+        .arg("cascade")
+        .arg("test_data/correct_trivial.json")
+        .arg("--fail")
+        .arg("GCGB2S2KGYARPVIA37HYZXVRM2YZUEXA6S33ZU5BUDC6THSB62LZSTYH")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("cascade_rounds: [[0]]"))
+        .stdout(predicate::str::contains(
+            "surviving_minimal_quorums: [[1,2]]",
+        ))
+        .stdout(predicate::str::contains("network_halts: false"));
+    Ok(())
+}
+
+#[test]
+fn cascade_of_organizations_can_halt_network() -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("fbas_analyzer")?
+        .arg("--results-only")
+        .arg("cascade")
+        .arg("test_data/stellarbeat_nodes_2019-09-17.json")
+        .arg("--organizations")
+        .arg("test_data/stellarbeat_organizations_2019-09-17.json")
+        .arg("--fail")
+        .arg("Stellar Development Foundation")
+        .arg("--fail")
+        .arg("LOBSTR")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("surviving_nodes: []"))
+        .stdout(predicate::str::contains("network_halts: true"));
+    Ok(())
+}
+
+#[test]
+fn diff_reports_changes_between_snapshots() -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("fbas_analyzer")?
+        .arg("diff")
+        .arg("test_data/correct_trivial.json")
+        .arg("test_data/broken_trivial.json")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("added_nodes: []"))
+        .stdout(predicate::str::contains(
+            r#"changed_quorum_sets: [{"publicKey":"GCGB2S2KGYARPVIA37HYZXVRM2YZUEXA6S33ZU5BUDC6THSB62LZSTYH","oldThreshold":2,"newThreshold":1,"addedValidators":[],"removedValidators":[]}]"#,
+        ))
+        .stdout(predicate::str::contains("top_tier_size: [3,3]"))
+        .stdout(predicate::str::contains("smallest_splitting_set_size: [1,0]"));
+    Ok(())
+}
+
+#[test]
+fn json_report_contains_all_results() -> Result<(), Box<dyn std::error::Error>> {
+    let output = Command::cargo_bin("fbas_analyzer")?
+        .arg("test_data/correct_trivial.json")
+        .arg("-a")
+        .arg("--format")
+        .arg("json")
+        .output()?;
+    assert!(output.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(1, report["schema_version"]);
+    assert!(report["merged_by"].is_null());
+    let results = &report["results"];
+    assert_eq!(
+        serde_json::json!(true),
+        results["has_quorum_intersection"]["value"]
+    );
+    assert_eq!(
+        serde_json::json!([[0, 1], [0, 2], [1, 2]]),
+        results["minimal_quorums"]["value"]
+    );
+    assert_eq!(
+        serde_json::json!([3, 3, [2, 2, 2.0], [0, 0, 3]]),
+        results["minimal_blocking_sets"]["description"]
+    );
+    assert_eq!(
+        serde_json::json!(false),
+        results["minimal_splitting_sets"]["partial"]
+    );
+    assert!(results["minimal_splitting_sets"]["duration_secs"].is_f64());
+    assert_eq!(serde_json::json!([0, 1, 2]), results["top_tier"]["value"]);
+    assert!(results["symmetric_clusters"].is_object());
+    Ok(())
+}
+
+#[test]
+fn json_report_with_describe_and_merging() -> Result<(), Box<dyn std::error::Error>> {
+    let output = Command::cargo_bin("fbas_analyzer")?
+        .arg("test_data/stellarbeat_nodes_2019-09-17.json")
+        .arg("--merge-by-org")
+        .arg("test_data/stellarbeat_organizations_2019-09-17.json")
+        .arg("-b")
+        .arg("-d")
+        .arg("-p")
+        .arg("--format")
+        .arg("json")
+        .output()?;
+    assert!(output.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!("organization", report["merged_by"]);
+    assert_eq!(serde_json::json!(true), report["pretty"]);
+    let minimal_blocking_sets = &report["results"]["minimal_blocking_sets"];
+    assert!(minimal_blocking_sets.get("value").is_none());
+    assert_eq!(10, minimal_blocking_sets["description"][0]);
+    assert_eq!(
+        5,
+        report["results"]["top_tier"]["value"]
+            .as_array()
+            .unwrap()
+            .len()
+    );
+    Ok(())
+}
+
+#[test]
+fn server_analyzes_posted_fbas_and_reuses_results() -> Result<(), Box<dyn std::error::Error>> {
+    use std::io::{BufRead, BufReader, Read, Write};
+
+    /// Makes sure that the server gets stopped even if the test fails.
+    struct ServerProcess(std::process::Child);
+    impl Drop for ServerProcess {
+        fn drop(&mut self) {
+            self.0.kill().ok();
+            self.0.wait().ok();
+        }
+    }
+    let mut server = ServerProcess(
+        std::process::Command::new(assert_cmd::cargo::cargo_bin("fbas_analyzer"))
+            .arg("serve")
+            .arg("--port")
+            .arg("0")
+            .arg("--max-cached-analyses")
+            .arg("1")
+            .stderr(std::process::Stdio::piped())
+            .spawn()?,
+    );
+    let mut stderr = BufReader::new(server.0.stderr.take().unwrap());
+    let mut line = String::new();
+    stderr.read_line(&mut line)?;
+    let address = line
+        .trim()
+        .trim_start_matches("Listening on http://")
+        .to_string();
+
+    let post = |body: &str| -> Result<(String, serde_json::Value), Box<dyn std::error::Error>> {
+        let mut stream = std::net::TcpStream::connect(&address)?;
+        write!(
+            stream,
+            "POST /analyze HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\n\
+             Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+            address,
+            body.len(),
+            body
+        )?;
+        let mut response = String::new();
+        stream.read_to_string(&mut response)?;
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        let status_line = head.lines().next().unwrap().to_string();
+        Ok((status_line, serde_json::from_str(body)?))
+    };
+    let nodes = std::fs::read_to_string("test_data/correct_trivial.json")?;
+    let request = format!(r#"{{ "nodes": {}, "timeout": 10 }}"#, nodes);
+
+    let (status_line, response) = post(&request)?;
+    assert!(status_line.contains("200"));
+    assert_eq!(serde_json::json!(false), response["cache_hit"]);
+    assert_eq!(serde_json::json!(false), response["partial"]);
+    assert_eq!(serde_json::json!(true), response["has_quorum_intersection"]);
+    assert_eq!(3, response["top_tier"].as_array().unwrap().len());
+    assert_eq!(
+        3,
+        response["minimal_blocking_sets"].as_array().unwrap().len()
+    );
+    assert_eq!(
+        3,
+        response["minimal_splitting_sets"].as_array().unwrap().len()
+    );
+
+    assert_eq!(serde_json::json!(10), response["timeout"]);
+    // a shared analysis keeps the timeout of the request that started it
+    let (_, cached_response) = post(&format!(r#"{{ "nodes": {}, "timeout": 20 }}"#, nodes))?;
+    assert_eq!(serde_json::json!(true), cached_response["cache_hit"]);
+    assert_eq!(serde_json::json!(10), cached_response["timeout"]);
+    assert_eq!(
+        response["minimal_splitting_sets"],
+        cached_response["minimal_splitting_sets"]
+    );
+
+    // concurrent requests for the same FBAS share one analysis
+    let nodes = std::fs::read_to_string("test_data/correct.json")?;
+    let request = format!(r#"{{ "nodes": {} }}"#, nodes);
+    let responses: Vec<serde_json::Value> = std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..4)
+            .map(|_| scope.spawn(|| post(&request).unwrap().1))
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+    assert_eq!(
+        1,
+        responses
+            .iter()
+            .filter(|response| response["cache_hit"] == serde_json::json!(false))
+            .count()
+    );
+    assert!(responses.iter().all(
+        |response| response["minimal_splitting_sets"] == responses[0]["minimal_splitting_sets"]
+    ));
+    // only one analysis is kept, so the first one has been dropped
+    let (_, evicted_response) = post(&format!(
+        r#"{{ "nodes": {} }}"#,
+        std::fs::read_to_string("test_data/correct_trivial.json")?
+    ))?;
+    assert_eq!(serde_json::json!(false), evicted_response["cache_hit"]);
+    assert_eq!(serde_json::Value::Null, evicted_response["timeout"]);
+
+    let (status_line, error_response) = post(r#"{ "nodes": 5 }"#)?;
+    assert!(status_line.contains("400"));
+    assert!(error_response["error"].is_string());
+    Ok(())
+}
+
+#[test]
+fn watch_appends_history_and_skips_unchanged_snapshots() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = std::env::temp_dir().join(format!(
+        "fbas_analyzer_cli_test_watch_{}",
+        std::process::id()
+    ));
+    std::fs::create_dir_all(&dir)?;
+    for file_name in [
+        "stellarbeat_nodes_2019-09-17.json",
+        "stellarbeat_organizations_2019-09-17.json",
+    ] {
+        std::fs::copy(format!("test_data/{}", file_name), dir.join(file_name))?;
+    }
+    let history_path = dir.join("history.jsonl");
+    let watch_once = || -> Result<std::process::Output, Box<dyn std::error::Error>> {
+        Ok(Command::cargo_bin("fbas_analyzer")?
+            .arg("watch")
+            .arg(&dir)
+            .arg("--once")
+            .arg("--min-blocking-set-size")
+            .arg("3")
+            .output()?)
+    };
+
+    let first = watch_once()?;
+    let second = watch_once()?;
+    std::fs::copy(
+        "test_data/stellarbeat_nodes_2020-01-16_broken_by_hand.json",
+        dir.join("stellarbeat_nodes_2020-01-16.json"),
+    )?;
+    let third = watch_once()?;
+    let history = std::fs::read_to_string(&history_path)?;
+    std::fs::remove_dir_all(&dir)?;
+
+    assert!(first.status.success());
+    assert!(String::from_utf8(first.stdout)?.contains("smallest blocking set has size 2 (below 3)"));
+    assert!(second.status.success());
+    assert!(second.stdout.is_empty());
+    assert!(String::from_utf8(second.stderr)?.contains("unchanged; skipping"));
+    assert!(third.status.success());
+    assert!(String::from_utf8(third.stdout)?.contains("quorum intersection lost"));
+
+    let records: Vec<serde_json::Value> = history
+        .lines()
+        .map(serde_json::from_str)
+        .collect::<Result<_, _>>()?;
+    assert_eq!(2, records.len());
+    assert_eq!(
+        serde_json::json!(true),
+        records[0]["has_quorum_intersection"]
+    );
+    assert_eq!(
+        serde_json::json!(2),
+        records[0]["smallest_blocking_set_size"]
+    );
+    assert_eq!(
+        serde_json::json!(false),
+        records[1]["has_quorum_intersection"]
+    );
+    assert!(records[1]["organizations_file"].is_null());
+    Ok(())
+}
+
+#[test]
+fn watch_reanalyzes_after_partial_results_and_changed_organizations(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = std::env::temp_dir().join(format!(
+        "fbas_analyzer_cli_test_watch_orgs_{}",
+        std::process::id()
+    ));
+    std::fs::create_dir_all(&dir)?;
+    std::fs::copy("test_data/correct_trivial.json", dir.join("nodes_1.json"))?;
+    let organizations_path = dir.join("organizations_1.json");
+    let sdf_validators = r#"[
+        "GCGB2S2KGYARPVIA37HYZXVRM2YZUEXA6S33ZU5BUDC6THSB62LZSTYH",
+        "GABMKJM6I25XI4K7U6XWMULOUQIQ27BCTMLS6BYYSOWKTBUXVRJSXHYQ",
+        "GCM6QMP3DLRPTAZW2UZPCPX2LF3SXWXKPMP3GKFZBDSF3QZGV2G5QSTK"
+    ]"#;
+    std::fs::write(
+        &organizations_path,
+        format!(
+            r#"[{{ "id": "sdf", "name": "SDF", "validators": {} }}]"#,
+            sdf_validators
+        ),
+    )?;
+    let watch_once = |timeout: Option<&str>| -> Result<String, Box<dyn std::error::Error>> {
+        let mut command = Command::cargo_bin("fbas_analyzer")?;
+        if let Some(timeout) = timeout {
+            command.arg("--timeout").arg(timeout);
+        }
+        command.arg("watch").arg(&dir).arg("--once");
+        Ok(String::from_utf8(command.output()?.stderr)?)
+    };
+
+    let timed_out = watch_once(Some("0"))?;
+    let after_partial = watch_once(None)?;
+    let unchanged = watch_once(None)?;
+    std::fs::write(
+        &organizations_path,
+        r#"[
+            { "id": "a", "name": "A", "validators": ["GCGB2S2KGYARPVIA37HYZXVRM2YZUEXA6S33ZU5BUDC6THSB62LZSTYH"] },
+            { "id": "b", "name": "B", "validators": ["GABMKJM6I25XI4K7U6XWMULOUQIQ27BCTMLS6BYYSOWKTBUXVRJSXHYQ"] }
+        ]"#,
+    )?;
+    let changed_organizations = watch_once(None)?;
+    let history = std::fs::read_to_string(dir.join("history.jsonl"))?;
+    std::fs::remove_dir_all(&dir)?;
+
+    assert!(timed_out.contains("Appended results"));
+    assert!(after_partial.contains("Appended results"));
+    assert!(unchanged.contains("unchanged; skipping"));
+    assert!(changed_organizations.contains("Appended results"));
+
+    let records: Vec<serde_json::Value> = history
+        .lines()
+        .map(serde_json::from_str)
+        .collect::<Result<_, _>>()?;
+    assert_eq!(3, records.len());
+    assert_eq!(serde_json::json!(true), records[0]["partial"]);
+    assert_eq!(serde_json::json!(false), records[1]["partial"]);
+    assert_eq!(records[1]["fbas_hash"], records[2]["fbas_hash"]);
+    assert_ne!(
+        records[1]["organizations_hash"],
+        records[2]["organizations_hash"]
+    );
+    assert_eq!(serde_json::json!(1), records[1]["top_tier_size"]);
+    assert_eq!(serde_json::json!(3), records[2]["top_tier_size"]);
+    Ok(())
+}
+
+#[test]
+fn watch_records_no_set_size_if_there_are_no_sets() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = std::env::temp_dir().join(format!(
+        "fbas_analyzer_cli_test_watch_no_sets_{}",
+        std::process::id()
+    ));
+    std::fs::create_dir_all(&dir)?;
+    // a single node can't be split
+    std::fs::write(
+        dir.join("nodes_1.json"),
+        r#"[{ "publicKey": "n0", "quorumSet": { "threshold": 1, "validators": ["n0"] } }]"#,
+    )?;
+    std::fs::write(
+        dir.join("organizations_1.json"),
+        r#"[{ "id": "a", "name": "A", "validators": ["n0"] }]"#,
+    )?;
+    let output = Command::cargo_bin("fbas_analyzer")?
+        .arg("watch")
+        .arg(&dir)
+        .arg("--once")
+        .arg("--min-splitting-set-size")
+        .arg("1")
+        .output()?;
+    let history = std::fs::read_to_string(dir.join("history.jsonl"))?;
+    std::fs::remove_dir_all(&dir)?;
+
+    assert!(!String::from_utf8(output.stdout)?.contains("ALERT"));
+    let record: serde_json::Value = serde_json::from_str(history.trim())?;
+    assert_eq!(serde_json::json!(false), record["partial"]);
+    assert_eq!(serde_json::json!(1), record["smallest_blocking_set_size"]);
+    assert_eq!(
+        serde_json::Value::Null,
+        record["smallest_splitting_set_size"]
+    );
+    Ok(())
+}
+
+#[test]
+fn satisfied_assertions_exit_successfully() -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("fbas_analyzer")?
+        .arg("test_data/correct.json")
+        .arg("--results-only")
+        .arg("--require-quorum-intersection")
+        .arg("--min-blocking-set-size")
+        .arg("2")
+        .arg("--min-splitting-set-size")
+        .arg("1")
+        .arg("--max-top-tier-size")
+        .arg("3")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Assertion failed").not());
+    Ok(())
+}
+
+#[test]
+fn failed_assertions_exit_with_distinct_codes() -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("fbas_analyzer")?
+        .arg("test_data/broken_trivial.json")
+        .arg("--results-only")
+        .arg("--require-quorum-intersection")
+        .arg("--max-top-tier-size")
+        .arg("2")
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains(
+            "Assertion failed: no quorum intersection; non-intersecting quorums: [[",
+        ))
+        .stderr(predicate::str::contains(
+            "Assertion failed: top tier has size 3 (more than 2): [",
+        ));
+    Command::cargo_bin("fbas_analyzer")?
+        .arg("test_data/correct.json")
+        .arg("--results-only")
+        .arg("--min-splitting-set-size")
+        .arg("2")
+        .assert()
+        .code(5)
+        .stderr(predicate::str::contains(
+            r#"smallest splitting set has size 1 (less than 2): ["GCGB2S2KGYARPVIA37HYZXVRM2YZUEXA6S33ZU5BUDC6THSB62LZSTYH"]"#,
+        ));
+    Ok(())
+}
+
+#[test]
+fn missing_quorums_fail_quorum_intersection_assertion() -> Result<(), Box<dyn std::error::Error>> {
+    let no_quorum_fbas = r#"[
+        { "publicKey": "n0", "quorumSet": { "threshold": 2, "validators": ["n0", "n1"] } },
+        { "publicKey": "n1", "quorumSet": { "threshold": 3, "validators": ["n0", "n1"] } }
+    ]"#;
+    Command::cargo_bin("fbas_analyzer")?
+        .arg("--results-only")
+        .arg("--require-quorum-intersection")
+        .write_stdin(no_quorum_fbas)
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("no quorum exists"));
+    Ok(())
+}
+
+#[test]
+fn quorum_intersection_witness_uses_group_names_when_merging(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let split_fbas = r#"[
+        {
+            "publicKey": "n0",
+            "quorumSet": { "threshold": 1, "validators": ["n0"] },
+            "geoData": { "countryName": "Oceania" }
+        },
+        {
+            "publicKey": "n1",
+            "quorumSet": { "threshold": 1, "validators": ["n1"] },
+            "geoData": { "countryName": "Eastasia" }
+        }
+    ]"#;
+    let fbas_path = std::env::temp_dir().join(format!(
+        "fbas_analyzer_cli_test_split_{}.json",
+        std::process::id()
+    ));
+    std::fs::write(&fbas_path, split_fbas)?;
+    let assert = Command::cargo_bin("fbas_analyzer")?
+        .arg(&fbas_path)
+        .arg("--merge-by-country")
+        .arg("--results-only")
+        .arg("--require-quorum-intersection")
+        .assert();
+    std::fs::remove_file(&fbas_path)?;
+    assert.code(3).stderr(predicate::str::contains(
+        r#"non-intersecting quorums: [["Oceania"],["Eastasia"]]"#,
+    ));
+    Ok(())
+}
+
+#[test]
+fn assertions_are_checked_after_merging() -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("fbas_analyzer")?
+        .arg("test_data/stellarbeat_nodes_2019-09-17.json")
+        .arg("--merge-by-org")
+        .arg("test_data/stellarbeat_organizations_2019-09-17.json")
+        .arg("--results-only")
+        .arg("--min-blocking-set-size")
+        .arg("3")
+        .assert()
+        .code(4)
+        .stderr(predicate::str::contains(
+            r#"smallest blocking set has size 2 (less than 3): ["Stellar Development Foundation","LOBSTR"]"#,
+        ));
+    Ok(())
+}
+
+#[test]
+fn timed_out_assertions_are_undecided() -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("fbas_analyzer")?
+        .arg("test_data/stellarbeat_nodes_2019-09-17.json")
+        .arg("--results-only")
+        .arg("--timeout")
+        .arg("0")
+        .arg("--min-splitting-set-size")
+        .arg("1")
+        .assert()
+        .code(7)
+        .stderr(predicate::str::contains(
+            "timed out before the smallest splitting set could be found",
+        ));
+    Ok(())
+}